@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, Transfer, Mint, TokenAccount, MintTo};
+use anchor_spl::token::{self, Token, Transfer, Mint, TokenAccount, MintTo, CloseAccount, Burn, FreezeAccount, ThawAccount};
 use anchor_spl::associated_token::AssociatedToken;
 
 declare_id!("7BwJmWypzV9WokmhxHZEjisoiBmpNhzcCnr8wQX3Kn9w");
@@ -22,6 +22,12 @@ pub mod real_estate_platform {
         platform_state.total_value_locked = 0;
         platform_state.sol_usd_price = 0; // Will be updated via Chainlink
         platform_state.last_price_update = Clock::get()?.unix_timestamp;
+        platform_state.trading_fee = 0;
+        platform_state.price_staleness_window = PlatformState::DEFAULT_PRICE_STALENESS_WINDOW;
+        platform_state.oracle_program = Pubkey::default();
+        platform_state.max_confidence_bps = PlatformState::DEFAULT_MAX_CONFIDENCE_BPS;
+        platform_state.twap_window = 0;
+        platform_state.value_locked_by_type = [0; PropertyType::COUNT];
         
         emit!(PlatformInitialized {
             authority: ctx.accounts.authority.key(),
@@ -32,7 +38,115 @@ pub mod real_estate_platform {
         Ok(())
     }
 
+    /// Initialize the platform treasury that collects secondary market trading fees
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_collected = 0;
+        treasury.total_withdrawn = 0;
+        treasury.bump = ctx.bumps.treasury;
+
+        Ok(())
+    }
+
+    /// Update the trading fee charged on secondary market trades
+    pub fn update_trading_fee(
+        ctx: Context<UpdateTradingFee>,
+        trading_fee: u64, // Fee in basis points (e.g., 50 = 0.5%)
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(trading_fee <= 10000, ErrorCode::InvalidFee);
+
+        let platform_state = &mut ctx.accounts.platform_state;
+        let old_trading_fee = platform_state.trading_fee;
+        platform_state.trading_fee = trading_fee;
+
+        emit!(TradingFeeUpdated {
+            old_trading_fee,
+            new_trading_fee: trading_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw collected trading fees from the platform treasury
+    pub fn withdraw_treasury(
+        ctx: Context<WithdrawTreasury>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        // Keep the treasury account rent exempt
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(treasury_info.data_len());
+        let available = treasury_info
+            .lamports()
+            .checked_sub(rent_exempt_minimum)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(amount <= available, ErrorCode::InsufficientFunds);
+
+        **treasury_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.destination.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_withdrawn = treasury
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(TreasuryWithdrawn {
+            destination: ctx.accounts.destination.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw platform fees collected in an SPL mint from a treasury-owned token account
+    pub fn withdraw_treasury_tokens(
+        ctx: Context<WithdrawTreasuryTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let seeds = &[b"treasury".as_ref(), &[ctx.accounts.treasury.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.treasury_token_account.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        emit!(TreasuryWithdrawn {
+            destination: ctx.accounts.destination.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     /// Initialize a new property for tokenization with Chainlink verification
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_property(
         ctx: Context<InitializeProperty>,
         property_id: String,
@@ -69,9 +183,25 @@ pub mod real_estate_platform {
         property.kyc_required = true;
         property.expected_rental_yield = 0; // Will be set later
         property.property_vault = ctx.accounts.property_owner.key(); // Simplified vault setup
-        
+        property.royalty_bps = 0;
+        property.payment_mint = None;
+        property.price_in_usd = false;
+        property.appraiser = Pubkey::default();
+        property.appraisal_report_hash = [0; 32];
+        property.appraisal_effective_date = 0;
+        property.in_funding_round = false;
+        property.min_purchase = 0;
+        property.max_purchase = 0;
+        property.max_ownership_bps = 0;
+        property.public_sale_starts_at = 0;
+        property.lockup_cliff = 0;
+        property.lockup_duration = 0;
+        property.income_per_token = 0;
+        property.income_mint = None;
+        property.distribution_count = 0;
+
         platform_state.total_properties += 1;
-        platform_state.total_value_locked += chainlink_valuation;
+        platform_state.adjust_value_locked(&property.property_type, 0, chainlink_valuation)?;
         
         emit!(PropertyInitialized {
             property_id: property_id.clone(),
@@ -111,36 +241,192 @@ pub mod real_estate_platform {
         Ok(())
     }
 
-    /// Update property valuation using Chainlink oracle data
+    /// Update property valuation from the median of its configured oracle feeds.
+    /// `force` lets the platform authority bypass the deviation guard.
     pub fn update_property_valuation(
         ctx: Context<UpdatePropertyValuation>,
-        new_valuation: u64,
-        chainlink_round_id: u64,
+        force: bool,
     ) -> Result<()> {
+        let platform_state = &mut ctx.accounts.platform_state;
+        let oracle_config = &ctx.accounts.oracle_config;
         let property = &mut ctx.accounts.property;
         
-        // Verify the caller is authorized (oracle or property owner)
-        require!(
-            ctx.accounts.authority.key() == property.owner || 
-            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
-            ErrorCode::Unauthorized
-        );
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        if force {
+            require!(
+                ctx.accounts.authority.key() == platform_state.authority,
+                ErrorCode::Unauthorized
+            );
+        }
+
+        // remaining_accounts: [feed_1, feed_2, ...]
+        let reading = oracle_config.aggregate(
+            ctx.remaining_accounts,
+            platform_state,
+            Clock::get()?.unix_timestamp,
+        )?;
         
         let old_valuation = property.property_valuation;
-        property.property_valuation = new_valuation;
-        property.last_valuation_update = Clock::get()?.unix_timestamp;
+        if !oracle_config.within_deviation(old_valuation, reading.value)? {
+            require!(force, ErrorCode::PriceDeviationTooLarge);
+            emit!(OracleDeviationOverridden {
+                subject: property.key(),
+                old_value: old_valuation,
+                new_value: reading.value,
+                authority: ctx.accounts.authority.key(),
+            });
+        }
+        property.property_valuation = reading.value;
+        property.last_valuation_update = reading.publish_time;
+        platform_state.adjust_value_locked(&property.property_type, old_valuation, reading.value)?;
+
+        if let Some(price_history) = ctx.accounts.price_history.as_mut() {
+            price_history.record(Clock::get()?.unix_timestamp, reading.value, reading.round_id);
+        }
         
         emit!(PropertyValuationUpdated {
             property_id: property.property_id.clone(),
             old_valuation,
-            new_valuation,
-            chainlink_round_id,
+            new_valuation: reading.value,
+            chainlink_round_id: reading.round_id,
             timestamp: Clock::get()?.unix_timestamp,
         });
         
         Ok(())
     }
 
+    /// Register (or reactivate) a licensed appraiser
+    pub fn register_appraiser(
+        ctx: Context<RegisterAppraiser>,
+        license_id: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(license_id.len() <= 32, ErrorCode::LicenseIdTooLong);
+
+        let appraiser_record = &mut ctx.accounts.appraiser_record;
+        appraiser_record.appraiser = ctx.accounts.appraiser.key();
+        appraiser_record.license_id = license_id.clone();
+        appraiser_record.is_active = true;
+        appraiser_record.registered_at = Clock::get()?.unix_timestamp;
+        appraiser_record.bump = ctx.bumps.appraiser_record;
+
+        emit!(AppraiserRegistered {
+            appraiser: appraiser_record.appraiser,
+            license_id,
+            timestamp: appraiser_record.registered_at,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke an appraiser; past attestations stay on record
+    pub fn revoke_appraiser(ctx: Context<RevokeAppraiser>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let appraiser_record = &mut ctx.accounts.appraiser_record;
+        appraiser_record.is_active = false;
+
+        emit!(AppraiserRevoked {
+            appraiser: appraiser_record.appraiser,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update property valuation from an appraisal signed by a registered appraiser
+    pub fn submit_appraisal(
+        ctx: Context<SubmitAppraisal>,
+        valuation: u64,
+        report_hash: [u8; 32],
+        effective_date: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let property = &mut ctx.accounts.property;
+        let old_valuation = property.apply_appraisal(
+            ctx.accounts.appraiser.key(),
+            valuation,
+            report_hash,
+            effective_date,
+            now,
+        )?;
+        ctx.accounts.platform_state.adjust_value_locked(
+            &property.property_type,
+            old_valuation,
+            valuation,
+        )?;
+
+        if let Some(price_history) = ctx.accounts.price_history.as_mut() {
+            price_history.record(now, valuation, 0);
+        }
+
+        emit!(PropertyAppraised {
+            property_id: property.property_id.clone(),
+            appraiser: ctx.accounts.appraiser.key(),
+            old_valuation,
+            new_valuation: valuation,
+            report_hash,
+            effective_date,
+        });
+
+        Ok(())
+    }
+
+    /// Update property valuation from an appraisal report signed off-chain. The transaction
+    /// must carry an Ed25519 program instruction, immediately before this one, verifying the
+    /// appraiser's signature over `Property::appraisal_message`.
+    pub fn submit_signed_appraisal(
+        ctx: Context<SubmitSignedAppraisal>,
+        valuation: u64,
+        report_hash: [u8; 32],
+        effective_date: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let appraiser = ctx.accounts.appraiser_record.appraiser;
+        let property_key = ctx.accounts.property.key();
+
+        verify_ed25519_signature(
+            &ctx.accounts.instructions,
+            &appraiser,
+            &Property::appraisal_message(&property_key, valuation, &report_hash, effective_date),
+        )?;
+
+        let property = &mut ctx.accounts.property;
+        let old_valuation = property.apply_appraisal(
+            appraiser,
+            valuation,
+            report_hash,
+            effective_date,
+            now,
+        )?;
+        ctx.accounts.platform_state.adjust_value_locked(
+            &property.property_type,
+            old_valuation,
+            valuation,
+        )?;
+
+        if let Some(price_history) = ctx.accounts.price_history.as_mut() {
+            price_history.record(now, valuation, 0);
+        }
+
+        emit!(PropertyAppraised {
+            property_id: property.property_id.clone(),
+            appraiser,
+            old_valuation,
+            new_valuation: valuation,
+            report_hash,
+            effective_date,
+        });
+
+        Ok(())
+    }
+
     /// Update expected rental yield using Chainlink data
     pub fn update_rental_yield(
         ctx: Context<UpdateRentalYield>,
@@ -167,6 +453,127 @@ pub mod real_estate_platform {
         Ok(())
     }
 
+    /// Update the royalty paid to the property owner on secondary market trades
+    pub fn update_property_royalty(
+        ctx: Context<UpdatePropertyRoyalty>,
+        royalty_bps: u64, // Royalty in basis points (e.g., 100 = 1%)
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(royalty_bps <= 10000, ErrorCode::InvalidFee);
+
+        let old_royalty_bps = property.royalty_bps;
+        property.royalty_bps = royalty_bps;
+
+        emit!(PropertyRoyaltyUpdated {
+            property_id: property.property_id.clone(),
+            old_royalty_bps,
+            new_royalty_bps: royalty_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set an SPL payment mint (e.g. USDC) for primary sales, creating the property's payment vault.
+    /// Once set, `token_price` is denominated in the payment mint's base units.
+    pub fn set_payment_mint(ctx: Context<SetPaymentMint>) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(property.tokens_sold == 0, ErrorCode::SaleAlreadyStarted);
+        require!(!property.in_funding_round, ErrorCode::FundingRoundInProgress);
+        require!(!property.price_in_usd, ErrorCode::InvalidPaymentMint);
+
+        property.payment_mint = Some(ctx.accounts.payment_mint.key());
+
+        emit!(PaymentMintSet {
+            property_id: property.property_id.clone(),
+            payment_mint: ctx.accounts.payment_mint.key(),
+            payment_vault: ctx.accounts.payment_vault.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw primary-sale proceeds held in the payment vault to a token account of the
+    /// property owner
+    pub fn withdraw_payment_vault(ctx: Context<WithdrawPaymentVault>, amount: u64) -> Result<()> {
+        let property = &ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let property_key = property.key();
+        let seeds = &[b"payment_vault", property_key.as_ref(), &[ctx.bumps.payment_vault]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payment_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.payment_vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        emit!(PaymentVaultWithdrawn {
+            property_id: property.property_id.clone(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Update the primary sale token price, optionally denominated in USD (8 decimals).
+    /// USD prices are converted to lamports at the stored SOL/USD price when trading.
+    pub fn update_token_price(
+        ctx: Context<UpdateTokenPrice>,
+        token_price: u64,
+        price_in_usd: bool,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+        require!(!property.in_funding_round, ErrorCode::FundingRoundInProgress);
+        // SPL payment mints are priced in the mint's own units
+        require!(
+            !price_in_usd || property.payment_mint.is_none(),
+            ErrorCode::InvalidPaymentMint
+        );
+
+        let old_token_price = property.token_price;
+        property.token_price = token_price;
+        property.price_in_usd = price_in_usd;
+
+        emit!(TokenPriceUpdated {
+            property_id: property.property_id.clone(),
+            old_token_price,
+            new_token_price: token_price,
+            price_in_usd,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     /// Purchase property tokens with KYC verification and actual token minting
     pub fn purchase_tokens(
         ctx: Context<PurchaseTokens>,
@@ -176,6 +583,7 @@ pub mod real_estate_platform {
         let property = &ctx.accounts.property;
         
         require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(!property.in_funding_round, ErrorCode::FundingRoundInProgress);
         require!(amount > 0, ErrorCode::InvalidAmount);
         require!(
             property.tokens_sold + amount <= property.total_tokens,
@@ -190,37 +598,92 @@ pub mod real_estate_platform {
             );
         }
 
+        let holding_after = ctx
+            .accounts
+            .investor_record
+            .tokens_owned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        property.check_offering_rules(
+            amount,
+            holding_after,
+            Clock::get()?.unix_timestamp,
+            ctx.accounts.whitelist_entry.as_deref().map(|e| &**e),
+        )?;
+
         let token_price = property.token_price;
-        let total_cost = amount
+        let mut total_cost = amount
             .checked_mul(token_price)
             .ok_or(ErrorCode::MathOverflow)?;
+        if property.price_in_usd {
+            total_cost = ctx
+                .accounts
+                .platform_state
+                .usd_to_lamports(
+                    total_cost,
+                    Clock::get()?.unix_timestamp,
+                    ctx.accounts.sol_price_history.as_deref().map(|h| &**h),
+                )?;
+        }
 
         // Store property key before mutable borrow
         let property_key = ctx.accounts.property.key();
 
-        // Transfer SOL from buyer to property vault
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &ctx.accounts.buyer.key(),
-            &ctx.accounts.property_vault.key(),
-            total_cost,
-        );
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                ctx.accounts.buyer.to_account_info(),
-                ctx.accounts.property_vault.to_account_info(),
-            ],
-        )?;
+        match property.payment_mint {
+            Some(payment_mint) => {
+                // Transfer payment tokens from buyer to property payment vault
+                let buyer_payment_account = ctx
+                    .accounts
+                    .buyer_payment_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let payment_vault = ctx
+                    .accounts
+                    .payment_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                require!(
+                    buyer_payment_account.mint == payment_mint,
+                    ErrorCode::InvalidPaymentMint
+                );
+
+                let cpi_accounts = Transfer {
+                    from: buyer_payment_account.to_account_info(),
+                    to: payment_vault.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token::transfer(cpi_ctx, total_cost)?;
+            }
+            None => {
+                // Transfer SOL from buyer to property vault
+                let ix = anchor_lang::solana_program::system_instruction::transfer(
+                    &ctx.accounts.buyer.key(),
+                    &ctx.accounts.property_vault.key(),
+                    total_cost,
+                );
+                anchor_lang::solana_program::program::invoke(
+                    &ix,
+                    &[
+                        ctx.accounts.buyer.to_account_info(),
+                        ctx.accounts.property_vault.to_account_info(),
+                    ],
+                )?;
+            }
+        }
 
         // Mint tokens to buyer
-        let cpi_accounts = MintTo {
-            mint: ctx.accounts.token_mint.to_account_info(),
-            to: ctx.accounts.buyer_token_account.to_account_info(),
-            authority: ctx.accounts.property_owner.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, amount)?;
+        mint_property_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.buyer_token_account.to_account_info(),
+            &ctx.accounts.mint_authority,
+            &property_key,
+            ctx.bumps.mint_authority,
+            property.total_tokens,
+            amount,
+        )?;
 
         // Update property
         let property = &mut ctx.accounts.property;
@@ -230,8 +693,22 @@ pub mod real_estate_platform {
         let investor_record = &mut ctx.accounts.investor_record;
         investor_record.investor = ctx.accounts.buyer.key();
         investor_record.property = property_key; // Use stored key instead of borrowing
+        investor_record.settle_income(property.income_per_token)?;
         investor_record.tokens_owned += amount;
-        investor_record.total_invested += total_cost;
+        if property.payment_mint.is_some() {
+            investor_record.total_invested_payment = investor_record
+                .total_invested_payment
+                .checked_add(total_cost)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            investor_record.total_invested += total_cost;
+        }
+        investor_record.add_lockup(
+            amount,
+            Clock::get()?.unix_timestamp,
+            property.lockup_cliff,
+            property.lockup_duration,
+        )?;
 
         emit!(TokensPurchased {
             property_id,
@@ -244,1295 +721,8026 @@ pub mod real_estate_platform {
         Ok(())
     }
 
-    /// List tokens for sale on secondary market (simplified)
-    pub fn list_tokens_for_sale(
-        ctx: Context<ListTokensForSale>,
-        amount: u64,
-        price_per_token: u64,
-        market_price_usd: u64, // Current market price from Chainlink
+    /// Set per-investor purchase limits and the early-access window for primary sales.
+    /// Zero disables a limit.
+    pub fn set_offering_rules(
+        ctx: Context<SetOfferingRules>,
+        min_purchase: u64,
+        max_purchase: u64,
+        max_ownership_bps: u64,
+        public_sale_starts_at: i64, // Only whitelisted investors can buy before this time
     ) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(price_per_token > 0, ErrorCode::InvalidTokenPrice);
+        let property = &mut ctx.accounts.property;
 
-        let market_listing = &mut ctx.accounts.market_listing;
-        market_listing.seller = ctx.accounts.seller.key();
-        market_listing.property = ctx.accounts.property.key();
-        market_listing.amount = amount;
-        market_listing.price_per_token = price_per_token;
-        market_listing.total_price = amount.checked_mul(price_per_token).ok_or(ErrorCode::MathOverflow)?;
-        market_listing.is_active = true;
-        market_listing.created_at = Clock::get()?.unix_timestamp;
-        market_listing.market_price_reference = market_price_usd;
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            max_purchase == 0 || min_purchase <= max_purchase,
+            ErrorCode::InvalidOfferingRules
+        );
+        require!(max_ownership_bps <= 10000, ErrorCode::InvalidOfferingRules);
 
-        emit!(TokensListedForSale {
-            property_id: ctx.accounts.property.property_id.clone(),
-            seller: ctx.accounts.seller.key(),
-            amount,
-            price_per_token,
-            market_price_reference: market_price_usd,
+        property.min_purchase = min_purchase;
+        property.max_purchase = max_purchase;
+        property.max_ownership_bps = max_ownership_bps;
+        property.public_sale_starts_at = public_sale_starts_at;
+
+        emit!(OfferingRulesUpdated {
+            property_id: property.property_id.clone(),
+            min_purchase,
+            max_purchase,
+            max_ownership_bps,
+            public_sale_starts_at,
         });
 
         Ok(())
     }
 
-    /// Purchase tokens from secondary market (simplified)
-    pub fn buy_from_market(
-        ctx: Context<BuyFromMarket>,
-        amount: u64,
+    /// Set the lock-up applied to tokens bought in the primary sale. Applies to purchases
+    /// made after the change.
+    pub fn set_lockup_schedule(
+        ctx: Context<SetLockupSchedule>,
+        cliff: i64,    // Seconds
+        duration: i64, // Seconds; 0 disables the lock-up
     ) -> Result<()> {
-        let market_listing = &mut ctx.accounts.market_listing;
-        
-        require!(market_listing.is_active, ErrorCode::ListingNotActive);
-        require!(amount <= market_listing.amount, ErrorCode::InsufficientTokens);
+        let property = &mut ctx.accounts.property;
 
-        let total_cost = amount
-            .checked_mul(market_listing.price_per_token)
-            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            cliff >= 0 && duration >= 0 && cliff <= duration,
+            ErrorCode::InvalidLockupSchedule
+        );
 
-        // Simplified implementation - just update the listing
-        // In a real implementation, you would handle SOL and token transfers
-        
-        // Update market listing
-        market_listing.amount -= amount;
-        if market_listing.amount == 0 {
-            market_listing.is_active = false;
-        }
+        property.lockup_cliff = cliff;
+        property.lockup_duration = duration;
 
-        emit!(TokensPurchasedFromMarket {
-            property_id: ctx.accounts.property.property_id.clone(),
-            seller: market_listing.seller,
-            buyer: ctx.accounts.buyer.key(),
-            amount,
-            total_cost,
+        emit!(LockupScheduleUpdated {
+            property_id: property.property_id.clone(),
+            cliff,
+            duration,
         });
 
         Ok(())
     }
 
-    /// Initiate property sale (requires governance vote)
-    pub fn initiate_property_sale(
-        ctx: Context<InitiatePropertySale>,
-        asking_price: u64,
-        chainlink_valuation: u64,
+    /// Grant an investor early access to a property's offering, optionally capping how many
+    /// tokens they can hold while the early-access window is open
+    pub fn add_to_whitelist(
+        ctx: Context<AddToWhitelist>,
+        allocation: u64, // 0 = only the property's offering rules apply
     ) -> Result<()> {
-        let property = &mut ctx.accounts.property;
-        
         require!(
-            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.property.owner ||
             ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
             ErrorCode::Unauthorized
         );
 
-        property.is_for_sale = true;
-        property.asking_price = asking_price;
-        property.market_valuation = chainlink_valuation;
-        property.sale_initiated_at = Clock::get()?.unix_timestamp;
+        let whitelist_entry = &mut ctx.accounts.whitelist_entry;
+        whitelist_entry.property = ctx.accounts.property.key();
+        whitelist_entry.investor = ctx.accounts.investor.key();
+        whitelist_entry.allocation = allocation;
+        whitelist_entry.bump = ctx.bumps.whitelist_entry;
 
-        emit!(PropertySaleInitiated {
-            property_id: property.property_id.clone(),
-            asking_price,
-            market_valuation: chainlink_valuation,
-            timestamp: Clock::get()?.unix_timestamp,
+        emit!(WhitelistUpdated {
+            property_id: ctx.accounts.property.property_id.clone(),
+            investor: whitelist_entry.investor,
+            allocation,
+            is_whitelisted: true,
         });
 
         Ok(())
     }
 
-    /// Execute property sale and distribute proceeds
-    pub fn execute_property_sale(
-        ctx: Context<ExecutePropertySale>,
-        sale_price: u64,
-        buyer_address: Pubkey,
-    ) -> Result<()> {
-        let property = &mut ctx.accounts.property;
-        let platform_state = &ctx.accounts.platform_state;
-        
-        require!(property.is_for_sale, ErrorCode::PropertyNotForSale);
+    /// Revoke an investor's early access
+    pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>) -> Result<()> {
         require!(
-            ctx.accounts.authority.key() == property.owner ||
-            ctx.accounts.authority.key() == platform_state.authority,
+            ctx.accounts.authority.key() == ctx.accounts.property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
             ErrorCode::Unauthorized
         );
 
-        // Calculate platform fee
-        let platform_fee = sale_price
-            .checked_mul(platform_state.platform_fee)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        let net_proceeds = sale_price
-            .checked_sub(platform_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        property.is_active = false;
-        property.is_for_sale = false;
-        property.final_sale_price = sale_price;
-        property.sale_completed_at = Clock::get()?.unix_timestamp;
-
-        emit!(PropertySold {
-            property_id: property.property_id.clone(),
-            sale_price,
-            platform_fee,
-            net_proceeds,
-            buyer: buyer_address,
-            timestamp: Clock::get()?.unix_timestamp,
+        emit!(WhitelistUpdated {
+            property_id: ctx.accounts.property.property_id.clone(),
+            investor: ctx.accounts.whitelist_entry.investor,
+            allocation: 0,
+            is_whitelisted: false,
         });
 
         Ok(())
     }
 
-    /// Distribute rental income to multiple investors in batch for gas efficiency
-    pub fn batch_distribute_rental_income(
-        ctx: Context<BatchDistributeRentalIncome>,
-        total_income: u64,
-        chainlink_round_id: u64,
-        investor_addresses: Vec<Pubkey>,
+    /// Open an offering phase for a property. Caps are in tokens; contributions are held in
+    /// escrow until the round is finalized.
+    pub fn create_funding_round(
+        ctx: Context<CreateFundingRound>,
+        round_id: u64,
+        start_time: i64,
+        end_time: i64,
+        soft_cap: u64,
+        hard_cap: u64,
     ) -> Result<()> {
         let property = &mut ctx.accounts.property;
-        let platform_state = &ctx.accounts.platform_state;
-        
+
         require!(
             ctx.accounts.authority.key() == property.owner ||
-            ctx.accounts.authority.key() == platform_state.authority,
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
             ErrorCode::Unauthorized
         );
-        
-        require!(total_income > 0, ErrorCode::InvalidAmount);
-        require!(property.tokens_sold > 0, ErrorCode::NoTokensIssued);
-        require!(investor_addresses.len() <= 50, ErrorCode::TooManyInvestors); // Limit batch size
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(!property.in_funding_round, ErrorCode::FundingRoundInProgress);
         require!(
-            ctx.remaining_accounts.len() == investor_addresses.len(),
-            ErrorCode::InvalidAccountsLength
+            start_time < end_time && end_time > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidFundingRound
+        );
+        require!(
+            soft_cap > 0 &&
+            soft_cap <= hard_cap &&
+            hard_cap <= property.total_tokens - property.tokens_sold,
+            ErrorCode::InvalidFundingRound
+        );
+        require!(
+            property.payment_mint.is_none() || ctx.accounts.round_vault.is_some(),
+            ErrorCode::MissingTokenAccount
         );
 
-        // Calculate platform fee
-        let platform_fee = total_income
-            .checked_mul(platform_state.platform_fee)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let funding_round = &mut ctx.accounts.funding_round;
+        funding_round.property = property.key();
+        funding_round.round_id = round_id;
+        funding_round.start_time = start_time;
+        funding_round.end_time = end_time;
+        funding_round.soft_cap = soft_cap;
+        funding_round.hard_cap = hard_cap;
+        funding_round.tokens_committed = 0;
+        funding_round.total_raised = 0;
+        funding_round.payment_mint = property.payment_mint;
+        funding_round.status = FundingRoundStatus::Active;
+        funding_round.income_per_token = 0;
+        funding_round.bump = ctx.bumps.funding_round;
+
+        property.in_funding_round = true;
+
+        emit!(FundingRoundCreated {
+            property_id: property.property_id.clone(),
+            funding_round: funding_round.key(),
+            start_time,
+            end_time,
+            soft_cap,
+            hard_cap,
+        });
 
-        let distributable_income = total_income
-            .checked_sub(platform_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
 
-        property.total_rental_income += distributable_income;
-        property.last_income_distribution = Clock::get()?.unix_timestamp;
+    /// Commit to buy `amount` tokens in a funding round. Payment is escrowed in the round
+    /// (SOL) or its vault (payment mint).
+    pub fn contribute_to_round(
+        ctx: Context<ContributeToRound>,
+        amount: u64,
+    ) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let property = &ctx.accounts.property;
+        let funding_round = &ctx.accounts.funding_round;
 
-        // Track total distributed for verification
-        let mut total_distributed = 0u64;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            funding_round.status == FundingRoundStatus::Active &&
+            current_time >= funding_round.start_time &&
+            current_time < funding_round.end_time,
+            ErrorCode::FundingRoundNotOpen
+        );
+        let tokens_committed = funding_round
+            .tokens_committed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(tokens_committed <= funding_round.hard_cap, ErrorCode::HardCapExceeded);
 
-        // Process each investor in the batch using remaining_accounts
-        for (i, investor_address) in investor_addresses.iter().enumerate() {
-            let investor_record_info = &ctx.remaining_accounts[i];
-            
-            // Deserialize the investor record
-            let investor_record_data = investor_record_info.try_borrow_data()?;
-            let investor_record = InvestorRecord::try_deserialize(&mut investor_record_data.as_ref())?;
-            
-            // Verify the investor record matches the provided address
+        if property.kyc_required {
             require!(
-                investor_record.investor == *investor_address,
-                ErrorCode::InvalidInvestorRecord
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
             );
+        }
 
-            if investor_record.tokens_owned > 0 {
-                // Calculate investor's share
-                let ownership_percentage = (investor_record.tokens_owned as u128)
-                    .checked_mul(10000u128)
-                    .ok_or(ErrorCode::MathOverflow)?
-                    .checked_div(property.tokens_sold as u128)
-                    .ok_or(ErrorCode::MathOverflow)? as u64;
-
-                let investor_share = distributable_income
-                    .checked_mul(ownership_percentage)
-                    .ok_or(ErrorCode::MathOverflow)?
-                    .checked_div(10000)
-                    .ok_or(ErrorCode::MathOverflow)?;
+        // Limits apply to tokens already held plus everything committed to this round
+        let holding_after = ctx
+            .accounts
+            .investor_record
+            .tokens_owned
+            .checked_add(ctx.accounts.contribution.tokens)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        property.check_offering_rules(
+            amount,
+            holding_after,
+            current_time,
+            ctx.accounts.whitelist_entry.as_deref().map(|e| &**e),
+        )?;
 
-                total_distributed = total_distributed
-                    .checked_add(investor_share)
-                    .ok_or(ErrorCode::MathOverflow)?;
+        let mut total_cost = amount
+            .checked_mul(property.token_price)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if property.price_in_usd {
+            total_cost = ctx
+                .accounts
+                .platform_state
+                .usd_to_lamports(
+                    total_cost,
+                    current_time,
+                    ctx.accounts.sol_price_history.as_deref().map(|h| &**h),
+                )?;
+        }
 
-                emit!(BatchRentalIncomeDistributed {
-                    property_id: property.property_id.clone(),
-                    investor: *investor_address,
-                    amount: investor_share,
-                    batch_id: chainlink_round_id,
-                });
+        match funding_round.payment_mint {
+            Some(payment_mint) => {
+                let contributor_payment_account = ctx
+                    .accounts
+                    .contributor_payment_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let round_vault = ctx
+                    .accounts
+                    .round_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                require!(
+                    contributor_payment_account.mint == payment_mint,
+                    ErrorCode::InvalidPaymentMint
+                );
+
+                let cpi_accounts = Transfer {
+                    from: contributor_payment_account.to_account_info(),
+                    to: round_vault.to_account_info(),
+                    authority: ctx.accounts.contributor.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token::transfer(cpi_ctx, total_cost)?;
+            }
+            None => {
+                let ix = anchor_lang::solana_program::system_instruction::transfer(
+                    &ctx.accounts.contributor.key(),
+                    &funding_round.key(),
+                    total_cost,
+                );
+                anchor_lang::solana_program::program::invoke(
+                    &ix,
+                    &[
+                        ctx.accounts.contributor.to_account_info(),
+                        ctx.accounts.funding_round.to_account_info(),
+                    ],
+                )?;
             }
         }
 
-        emit!(RentalIncomeDistributed {
+        let funding_round = &mut ctx.accounts.funding_round;
+        funding_round.tokens_committed = tokens_committed;
+        funding_round.total_raised = funding_round
+            .total_raised
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.funding_round = funding_round.key();
+        contribution.contributor = ctx.accounts.contributor.key();
+        contribution.tokens = contribution
+            .tokens
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        contribution.amount_paid = contribution
+            .amount_paid
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+        contribution.bump = ctx.bumps.contribution;
+
+        let investor_record = &mut ctx.accounts.investor_record;
+        investor_record.investor = ctx.accounts.contributor.key();
+        investor_record.property = property.key();
+
+        emit!(RoundContributionMade {
             property_id: property.property_id.clone(),
-            total_income,
-            platform_fee,
-            distributable_income,
-            chainlink_round_id,
-            timestamp: Clock::get()?.unix_timestamp,
+            funding_round: funding_round.key(),
+            contributor: ctx.accounts.contributor.key(),
+            amount,
+            total_cost,
+            tokens_committed,
         });
 
         Ok(())
     }
 
-    /// Batch transfer tokens to multiple recipients for gas efficiency
-    pub fn batch_transfer_tokens(
-        ctx: Context<BatchTransferTokens>,
-        transfers: Vec<TokenTransfer>,
-    ) -> Result<()> {
-        require!(transfers.len() <= 20, ErrorCode::TooManyTransfers); // Limit batch size
+    /// Close a funding round once it has ended or sold out. A round that reached its soft
+    /// cap releases the raised funds to the property; otherwise contributors can be refunded.
+    pub fn finalize_funding_round(ctx: Context<FinalizeFundingRound>) -> Result<()> {
+        let funding_round = &ctx.accounts.funding_round;
+
         require!(
-            ctx.remaining_accounts.len() == transfers.len(),
-            ErrorCode::InvalidAccountsLength
+            funding_round.status == FundingRoundStatus::Active,
+            ErrorCode::FundingRoundFinalized
         );
-        
-        let property = &ctx.accounts.property;
-        let from_record = &mut ctx.accounts.from_investor_record;
-        
-        // Calculate total tokens being transferred
-        let mut total_amount = 0u64;
-        for transfer in &transfers {
-            require!(transfer.amount > 0, ErrorCode::InvalidAmount);
-            total_amount = total_amount
-                .checked_add(transfer.amount)
+        require!(
+            Clock::get()?.unix_timestamp >= funding_round.end_time ||
+            funding_round.tokens_committed == funding_round.hard_cap,
+            ErrorCode::FundingRoundNotEnded
+        );
+
+        let succeeded = funding_round.tokens_committed >= funding_round.soft_cap;
+        if succeeded {
+            let total_raised = funding_round.total_raised;
+            match funding_round.payment_mint {
+                Some(_) => {
+                    let round_vault = ctx
+                        .accounts
+                        .round_vault
+                        .as_ref()
+                        .ok_or(ErrorCode::MissingTokenAccount)?;
+                    let payment_vault = ctx
+                        .accounts
+                        .payment_vault
+                        .as_ref()
+                        .ok_or(ErrorCode::MissingTokenAccount)?;
+                    let funding_round_key = funding_round.key();
+                    let seeds = &[
+                        b"funding_vault",
+                        funding_round_key.as_ref(),
+                        &[ctx.bumps.round_vault.ok_or(ErrorCode::MissingTokenAccount)?],
+                    ];
+                    let signer = &[&seeds[..]];
+                    let cpi_accounts = Transfer {
+                        from: round_vault.to_account_info(),
+                        to: payment_vault.to_account_info(),
+                        authority: round_vault.to_account_info(),
+                    };
+                    let cpi_program = ctx.accounts.token_program.to_account_info();
+                    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                    token::transfer(cpi_ctx, total_raised)?;
+                }
+                None => {
+                    let round_info = ctx.accounts.funding_round.to_account_info();
+                    **round_info.try_borrow_mut_lamports()? -= total_raised;
+                    **ctx.accounts.property_vault.try_borrow_mut_lamports()? += total_raised;
+                }
+            }
+
+            let property = &mut ctx.accounts.property;
+            property.tokens_sold = property
+                .tokens_sold
+                .checked_add(ctx.accounts.funding_round.tokens_committed)
                 .ok_or(ErrorCode::MathOverflow)?;
         }
-        
-        require!(from_record.tokens_owned >= total_amount, ErrorCode::InsufficientTokens);
 
-        // Process each transfer in the batch
-        for (i, transfer) in transfers.iter().enumerate() {
-            // For now, we'll emit the event and track the transfer
-            // The actual SPL token transfer would need to be handled differently
-            // to avoid lifetime issues in batch operations
-            
-            emit!(BatchTokensTransferred {
-                property_id: property.property_id.clone(),
-                from: ctx.accounts.from.key(),
-                to: transfer.recipient,
-                amount: transfer.amount,
-                batch_index: i as u8,
-            });
-        }
+        let property = &mut ctx.accounts.property;
+        property.in_funding_round = false;
 
-        // Update sender's record
-        from_record.tokens_owned -= total_amount;
+        let income_per_token = property.income_per_token;
+        let funding_round = &mut ctx.accounts.funding_round;
+        funding_round.income_per_token = income_per_token;
+        funding_round.status = if succeeded {
+            FundingRoundStatus::Succeeded
+        } else {
+            FundingRoundStatus::Failed
+        };
 
-        emit!(BatchTransferCompleted {
+        emit!(FundingRoundFinalized {
             property_id: property.property_id.clone(),
-            from: ctx.accounts.from.key(),
-            total_amount,
-            transfer_count: transfers.len() as u8,
+            funding_round: funding_round.key(),
+            succeeded,
+            tokens_committed: funding_round.tokens_committed,
+            total_raised: funding_round.total_raised,
         });
 
         Ok(())
     }
 
-    /// Batch update KYC status for multiple users for gas efficiency
-    pub fn batch_update_kyc_status(
-        ctx: Context<BatchUpdateKycStatus>,
-        kyc_updates: Vec<KycUpdate>,
-    ) -> Result<()> {
+    /// Mint a contributor's tokens from a successful funding round
+    pub fn claim_round_tokens(ctx: Context<ClaimRoundTokens>) -> Result<()> {
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
-            ErrorCode::Unauthorized
+            ctx.accounts.funding_round.status == FundingRoundStatus::Succeeded,
+            ErrorCode::FundingRoundNotSucceeded
         );
-        
-        require!(kyc_updates.len() <= 30, ErrorCode::TooManyKycUpdates); // Limit batch size
+
+        let contribution = &ctx.accounts.contribution;
+        mint_property_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.contributor_token_account.to_account_info(),
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.property.key(),
+            ctx.bumps.mint_authority,
+            ctx.accounts.property.total_tokens,
+            contribution.tokens,
+        )?;
+
+        let investor_record = &mut ctx.accounts.investor_record;
+        investor_record.investor = ctx.accounts.contributor.key();
+        investor_record.property = ctx.accounts.property.key();
+        investor_record.settle_income(ctx.accounts.property.income_per_token)?;
+        investor_record.tokens_owned = investor_record
+            .tokens_owned
+            .checked_add(contribution.tokens)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if ctx.accounts.funding_round.payment_mint.is_some() {
+            investor_record.total_invested_payment = investor_record
+                .total_invested_payment
+                .checked_add(contribution.amount_paid)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            investor_record.total_invested = investor_record
+                .total_invested
+                .checked_add(contribution.amount_paid)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        // The claimed tokens have counted towards tokens_sold since the round succeeded
+        let income_since_round = (contribution.tokens as u128)
+            .checked_mul(
+                ctx.accounts
+                    .property
+                    .income_per_token
+                    .checked_sub(ctx.accounts.funding_round.income_per_token)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?
+            / Property::INCOME_PRECISION;
+        investor_record.pending_income = investor_record
+            .pending_income
+            .checked_add(u64::try_from(income_since_round).map_err(|_| ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        investor_record.add_lockup(
+            contribution.tokens,
+            Clock::get()?.unix_timestamp,
+            ctx.accounts.property.lockup_cliff,
+            ctx.accounts.property.lockup_duration,
+        )?;
+
+        emit!(RoundTokensClaimed {
+            property_id: ctx.accounts.property.property_id.clone(),
+            funding_round: ctx.accounts.funding_round.key(),
+            contributor: ctx.accounts.contributor.key(),
+            amount: contribution.tokens,
+        });
+
+        Ok(())
+    }
+
+    /// Return a contributor's escrowed payment from a failed funding round
+    pub fn refund_contribution(ctx: Context<RefundContribution>) -> Result<()> {
+        let funding_round = &ctx.accounts.funding_round;
         require!(
-            ctx.remaining_accounts.len() == kyc_updates.len(),
-            ErrorCode::InvalidAccountsLength
+            funding_round.status == FundingRoundStatus::Failed,
+            ErrorCode::FundingRoundNotFailed
         );
 
-        let current_time = Clock::get()?.unix_timestamp;
-
-        // Process each KYC update in the batch using remaining_accounts
-        for (i, kyc_update) in kyc_updates.iter().enumerate() {
-            let kyc_record_info = &ctx.remaining_accounts[i];
-            
-            // Deserialize and update the KYC record
-            let mut kyc_record_data = kyc_record_info.try_borrow_mut_data()?;
-            let mut kyc_record = KycRecord::try_deserialize(&mut kyc_record_data.as_ref())?;
-            
-            // Verify the KYC record matches the provided user
-            require!(
-                kyc_record.user == kyc_update.user,
-                ErrorCode::InvalidKycRecord
-            );
+        let amount_paid = ctx.accounts.contribution.amount_paid;
+        match funding_round.payment_mint {
+            Some(_) => {
+                let round_vault = ctx
+                    .accounts
+                    .round_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let contributor_payment_account = ctx
+                    .accounts
+                    .contributor_payment_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let funding_round_key = funding_round.key();
+                let seeds = &[
+                    b"funding_vault",
+                    funding_round_key.as_ref(),
+                    &[ctx.bumps.round_vault.ok_or(ErrorCode::MissingTokenAccount)?],
+                ];
+                let signer = &[&seeds[..]];
+                let cpi_accounts = Transfer {
+                    from: round_vault.to_account_info(),
+                    to: contributor_payment_account.to_account_info(),
+                    authority: round_vault.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token::transfer(cpi_ctx, amount_paid)?;
+            }
+            None => {
+                let round_info = ctx.accounts.funding_round.to_account_info();
+                **round_info.try_borrow_mut_lamports()? -= amount_paid;
+                **ctx.accounts.contributor.try_borrow_mut_lamports()? += amount_paid;
+            }
+        }
 
-            kyc_record.is_verified = kyc_update.is_verified;
-            kyc_record.updated_at = current_time;
-            kyc_record.verification_provider = "Chainlink".to_string();
-            kyc_record.round_id = kyc_update.chainlink_round_id;
+        emit!(ContributionRefunded {
+            funding_round: ctx.accounts.funding_round.key(),
+            contributor: ctx.accounts.contributor.key(),
+            amount_refunded: amount_paid,
+        });
 
-            // Serialize the updated record back
-            let mut updated_data = Vec::new();
-            kyc_record.try_serialize(&mut updated_data)?;
-            kyc_record_data[..updated_data.len()].copy_from_slice(&updated_data);
+        Ok(())
+    }
 
-            emit!(BatchKycStatusUpdated {
-                user: kyc_update.user,
-                is_verified: kyc_update.is_verified,
-                updated_at: current_time,
-                batch_index: i as u8,
-            });
-        }
+    /// Open a Dutch-auction primary offering priced in lamports per token. The price declines
+    /// linearly from `start_price` to `floor_price`; with `uniform_clearing` every buyer ends
+    /// up paying the final clearing price and can reclaim the difference.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_dutch_offering(
+        ctx: Context<CreateDutchOffering>,
+        offering_id: u64,
+        start_price: u64,
+        floor_price: u64,
+        start_time: i64,
+        end_time: i64,
+        tokens_offered: u64,
+        uniform_clearing: bool,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
 
-        emit!(BatchKycUpdateCompleted {
-            total_updates: kyc_updates.len() as u8,
-            updated_at: current_time,
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(!property.in_funding_round, ErrorCode::FundingRoundInProgress);
+        require!(
+            property.payment_mint.is_none() && !property.price_in_usd,
+            ErrorCode::InvalidPaymentMint
+        );
+        require!(
+            floor_price > 0 && floor_price <= start_price,
+            ErrorCode::InvalidTokenPrice
+        );
+        require!(
+            start_time < end_time && end_time > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidFundingRound
+        );
+        require!(
+            tokens_offered > 0 && tokens_offered <= property.total_tokens - property.tokens_sold,
+            ErrorCode::InvalidFundingRound
+        );
+
+        let offering = &mut ctx.accounts.offering;
+        offering.property = property.key();
+        offering.offering_id = offering_id;
+        offering.start_price = start_price;
+        offering.floor_price = floor_price;
+        offering.start_time = start_time;
+        offering.end_time = end_time;
+        offering.tokens_offered = tokens_offered;
+        offering.tokens_sold = 0;
+        offering.total_raised = 0;
+        offering.last_price = 0;
+        offering.clearing_price = 0;
+        offering.uniform_clearing = uniform_clearing;
+        offering.is_finalized = false;
+        offering.bump = ctx.bumps.offering;
+
+        property.in_funding_round = true;
+
+        emit!(DutchOfferingCreated {
+            property_id: property.property_id.clone(),
+            offering: offering.key(),
+            start_price,
+            floor_price,
+            start_time,
+            end_time,
+            tokens_offered,
+            uniform_clearing,
         });
 
         Ok(())
     }
 
-    /// Batch claim rental income for multiple properties for gas efficiency
-    pub fn batch_claim_rental_income(
-        ctx: Context<BatchClaimRentalIncome>,
-        property_keys: Vec<Pubkey>,
+    /// Buy tokens from a Dutch offering at the current price. Tokens are minted immediately;
+    /// under uniform clearing the payment is escrowed in the offering until it is finalized.
+    pub fn buy_dutch_offering(
+        ctx: Context<BuyDutchOffering>,
+        amount: u64,
     ) -> Result<()> {
-        require!(property_keys.len() <= 10, ErrorCode::TooManyProperties); // Limit batch size
+        let current_time = Clock::get()?.unix_timestamp;
+        let property = &ctx.accounts.property;
+        let offering = &ctx.accounts.offering;
+
+        require!(amount > 0, ErrorCode::InvalidAmount);
         require!(
-            ctx.remaining_accounts.len() == property_keys.len() * 3, // 3 accounts per property
-            ErrorCode::InvalidAccountsLength
+            !offering.is_finalized &&
+            current_time >= offering.start_time &&
+            current_time < offering.end_time,
+            ErrorCode::FundingRoundNotOpen
+        );
+        let offering_tokens_sold = offering
+            .tokens_sold
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            offering_tokens_sold <= offering.tokens_offered,
+            ErrorCode::InsufficientTokens
         );
-        
-        let investor = &ctx.accounts.investor;
-        let mut total_claimed = 0u64;
 
-        // Process each property claim in the batch using remaining_accounts
-        // Pattern: [property, investor_record, vault] for each property
-        for (i, property_key) in property_keys.iter().enumerate() {
-            let base_index = i * 3;
-            let property_info = &ctx.remaining_accounts[base_index];
-            let investor_record_info = &ctx.remaining_accounts[base_index + 1];
-            let property_vault_info = &ctx.remaining_accounts[base_index + 2];
-            
-            // Verify the property matches
-            require!(property_info.key() == *property_key, ErrorCode::InvalidPropertyKey);
-            
-            // Deserialize property
-            let property_data = property_info.try_borrow_data()?;
-            let property = Property::try_deserialize(&mut property_data.as_ref())?;
-            
-            // Deserialize and update investor record
-            let mut investor_record_data = investor_record_info.try_borrow_mut_data()?;
-            let mut investor_record = InvestorRecord::try_deserialize(&mut investor_record_data.as_ref())?;
-            
-            require!(investor_record.tokens_owned > 0, ErrorCode::NoTokensOwned);
-            
-            // Calculate claimable amount
-            let ownership_percentage = (investor_record.tokens_owned as u128)
-                .checked_mul(10000u128)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(property.tokens_sold as u128)
-                .ok_or(ErrorCode::MathOverflow)? as u64;
+        if property.kyc_required {
+            require!(
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
+            );
+        }
 
-            let claimable_amount = property.total_rental_income
-                .checked_mul(ownership_percentage)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(10000)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_sub(investor_record.total_claimed)
-                .ok_or(ErrorCode::MathOverflow)?;
+        let holding_after = ctx
+            .accounts
+            .investor_record
+            .tokens_owned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        property.check_offering_rules(
+            amount,
+            holding_after,
+            current_time,
+            ctx.accounts.whitelist_entry.as_deref().map(|e| &**e),
+        )?;
 
-            if claimable_amount > 0 {
-                // Transfer SOL from property vault to investor
-                **property_vault_info.try_borrow_mut_lamports()? -= claimable_amount;
-                **investor.to_account_info().try_borrow_mut_lamports()? += claimable_amount;
+        let price = offering.current_price(current_time)?;
+        let total_cost = amount
+            .checked_mul(price)
+            .ok_or(ErrorCode::MathOverflow)?;
 
-                investor_record.total_claimed += claimable_amount;
-                investor_record.last_claim_time = Clock::get()?.unix_timestamp;
-                
-                // Serialize the updated investor record back
-                let mut updated_data = Vec::new();
-                investor_record.try_serialize(&mut updated_data)?;
-                investor_record_data[..updated_data.len()].copy_from_slice(&updated_data);
-                
-                total_claimed = total_claimed
-                    .checked_add(claimable_amount)
-                    .ok_or(ErrorCode::MathOverflow)?;
+        // Uniform clearing escrows payments so the difference can be refunded
+        let destination = if offering.uniform_clearing {
+            ctx.accounts.offering.to_account_info()
+        } else {
+            ctx.accounts.property_vault.to_account_info()
+        };
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.buyer.key(),
+            destination.key,
+            total_cost,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[ctx.accounts.buyer.to_account_info(), destination.clone()],
+        )?;
 
-                emit!(BatchRentalIncomeClaimed {
-                    property_id: property.property_id.clone(),
-                    investor: investor.key(),
-                    amount: claimable_amount,
-                    batch_index: i as u8,
-                });
-            }
-        }
+        let property_key = property.key();
+        mint_property_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.buyer_token_account.to_account_info(),
+            &ctx.accounts.mint_authority,
+            &property_key,
+            ctx.bumps.mint_authority,
+            property.total_tokens,
+            amount,
+        )?;
 
-        emit!(BatchClaimCompleted {
-            investor: investor.key(),
-            total_claimed,
-            properties_count: property_keys.len() as u8,
+        let offering = &mut ctx.accounts.offering;
+        offering.tokens_sold = offering_tokens_sold;
+        offering.total_raised = offering
+            .total_raised
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+        offering.last_price = price;
+
+        let property = &mut ctx.accounts.property;
+        property.tokens_sold = property
+            .tokens_sold
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let investor_record = &mut ctx.accounts.investor_record;
+        investor_record.investor = ctx.accounts.buyer.key();
+        investor_record.property = property_key;
+        investor_record.settle_income(property.income_per_token)?;
+        investor_record.tokens_owned = holding_after;
+        investor_record.total_invested = investor_record
+            .total_invested
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+        investor_record.add_lockup(
+            amount,
+            current_time,
+            property.lockup_cliff,
+            property.lockup_duration,
+        )?;
+
+        let purchase = &mut ctx.accounts.purchase;
+        purchase.offering = offering.key();
+        purchase.buyer = ctx.accounts.buyer.key();
+        purchase.tokens = purchase
+            .tokens
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        purchase.amount_paid = purchase
+            .amount_paid
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+        purchase.bump = ctx.bumps.purchase;
+
+        emit!(TokensPurchased {
+            property_id: property.property_id.clone(),
+            buyer: ctx.accounts.buyer.key(),
+            amount,
+            total_cost,
+            tokens_remaining: property.total_tokens - property.tokens_sold,
         });
 
         Ok(())
     }
 
-    /// Create a governance proposal
-    pub fn create_proposal(
-        ctx: Context<CreateProposal>,
-        title: String,
-        description: String,
-        proposal_type: ProposalType,
-        voting_period: i64,
-    ) -> Result<()> {
-        let property = &ctx.accounts.property;
-        let investor_record = &ctx.accounts.investor_record;
-        let platform_state = &ctx.accounts.platform_state;
-        
+    /// Close a Dutch offering once it has ended or sold out. The clearing price is the last
+    /// price paid when sold out, otherwise the floor; it becomes the property's token price.
+    pub fn finalize_dutch_offering(ctx: Context<FinalizeDutchOffering>) -> Result<()> {
+        let offering = &ctx.accounts.offering;
+
+        require!(!offering.is_finalized, ErrorCode::FundingRoundFinalized);
         require!(
-            investor_record.tokens_owned >= platform_state.governance_threshold,
-            ErrorCode::InsufficientTokensForProposal
+            Clock::get()?.unix_timestamp >= offering.end_time ||
+            offering.tokens_sold == offering.tokens_offered,
+            ErrorCode::FundingRoundNotEnded
         );
-        
-        require!(title.len() <= 50, ErrorCode::TitleTooLong);
-        require!(description.len() <= 200, ErrorCode::DescriptionTooLong);
-        require!(voting_period > 0, ErrorCode::InvalidVotingPeriod);
 
-        let proposal = &mut ctx.accounts.proposal;
-        let current_time = Clock::get()?.unix_timestamp;
-        
-        proposal.property = ctx.accounts.property.key();
-        proposal.proposer = ctx.accounts.proposer.key();
-        proposal.title = title.clone();
-        proposal.description = description;
-        proposal.proposal_type = proposal_type.clone();
-        proposal.votes_for = 0;
-        proposal.votes_against = 0;
-        proposal.total_votes = 0;
-        proposal.created_at = current_time;
-        proposal.voting_ends_at = current_time + voting_period;
-        proposal.executed = false;
-        proposal.passed = false;
+        let clearing_price = if offering.tokens_sold == offering.tokens_offered {
+            offering.last_price
+        } else {
+            offering.floor_price
+        };
 
-        emit!(ProposalCreated {
+        if offering.uniform_clearing {
+            let proceeds = offering
+                .tokens_sold
+                .checked_mul(clearing_price)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let offering_info = ctx.accounts.offering.to_account_info();
+            **offering_info.try_borrow_mut_lamports()? -= proceeds;
+            **ctx.accounts.property_vault.try_borrow_mut_lamports()? += proceeds;
+        }
+
+        let offering = &mut ctx.accounts.offering;
+        offering.clearing_price = clearing_price;
+        offering.is_finalized = true;
+
+        let property = &mut ctx.accounts.property;
+        property.in_funding_round = false;
+        if offering.tokens_sold > 0 {
+            property.token_price = clearing_price;
+        }
+
+        emit!(DutchOfferingFinalized {
             property_id: property.property_id.clone(),
-            proposer: ctx.accounts.proposer.key(),
-            title,
-            proposal_type,
-            voting_ends_at: proposal.voting_ends_at,
+            offering: offering.key(),
+            clearing_price,
+            tokens_sold: offering.tokens_sold,
+            total_raised: offering.total_raised,
         });
 
         Ok(())
     }
 
-    /// Vote on a governance proposal
-    pub fn vote_on_proposal(
-        ctx: Context<VoteOnProposal>,
-        vote_for: bool,
-    ) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal;
-        let investor_record = &ctx.accounts.investor_record;
-        let vote_record = &mut ctx.accounts.vote_record;
-        
-        let current_time = Clock::get()?.unix_timestamp;
-        require!(current_time <= proposal.voting_ends_at, ErrorCode::VotingPeriodEnded);
-        require!(investor_record.tokens_owned > 0, ErrorCode::NoTokensOwned);
-        require!(!vote_record.has_voted, ErrorCode::AlreadyVoted);
-
-        let voting_power = investor_record.tokens_owned;
-        
-        if vote_for {
-            proposal.votes_for += voting_power;
+    /// Refund the difference between what a buyer paid and the clearing price, closing their
+    /// purchase record. Without uniform clearing this only reclaims the record's rent.
+    pub fn claim_dutch_refund(ctx: Context<ClaimDutchRefund>) -> Result<()> {
+        let offering = &ctx.accounts.offering;
+        require!(offering.is_finalized, ErrorCode::OfferingNotFinalized);
+
+        let purchase = &ctx.accounts.purchase;
+        let amount_refunded = if offering.uniform_clearing {
+            purchase
+                .amount_paid
+                .checked_sub(
+                    purchase
+                        .tokens
+                        .checked_mul(offering.clearing_price)
+                        .ok_or(ErrorCode::MathOverflow)?,
+                )
+                .ok_or(ErrorCode::MathOverflow)?
         } else {
-            proposal.votes_against += voting_power;
+            0
+        };
+
+        if amount_refunded > 0 {
+            let offering_info = ctx.accounts.offering.to_account_info();
+            **offering_info.try_borrow_mut_lamports()? -= amount_refunded;
+            **ctx.accounts.buyer.try_borrow_mut_lamports()? += amount_refunded;
         }
-        
-        proposal.total_votes += voting_power;
-        
-        vote_record.voter = ctx.accounts.voter.key();
-        vote_record.proposal = ctx.accounts.proposal.key();
-        vote_record.vote_for = vote_for;
-        vote_record.voting_power = voting_power;
-        vote_record.has_voted = true;
-        vote_record.voted_at = current_time;
 
-        emit!(VoteCast {
-            proposal: ctx.accounts.proposal.key(),
-            voter: ctx.accounts.voter.key(),
-            vote_for,
-            voting_power,
+        emit!(DutchRefundClaimed {
+            offering: ctx.accounts.offering.key(),
+            buyer: ctx.accounts.buyer.key(),
+            tokens: purchase.tokens,
+            amount_refunded,
         });
 
         Ok(())
     }
 
-    /// Execute a passed proposal
-    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal;
-        let property = &ctx.accounts.property;
-        
+    /// List tokens for sale on secondary market, moving them into a listing escrow
+    pub fn list_tokens_for_sale(
+        ctx: Context<ListTokensForSale>,
+        amount: u64,
+        price_per_token: u64, // Lamports, or USD (8 decimals) for USD-priced properties
+        market_price_usd: u64, // Current market price from Chainlink
+        expires_at: Option<i64>, // None = good till cancelled
+    ) -> Result<()> {
         let current_time = Clock::get()?.unix_timestamp;
-        require!(current_time > proposal.voting_ends_at, ErrorCode::VotingStillActive);
-        require!(!proposal.executed, ErrorCode::ProposalAlreadyExecuted);
-        
-        // Check if proposal passed
-        let passed = proposal.votes_for > proposal.votes_against && 
-                    proposal.total_votes > property.tokens_sold / 2;
-        
-        proposal.passed = passed;
-        proposal.executed = true;
 
-        emit!(ProposalExecuted {
-            proposal: proposal.key(),
-            passed,
-            votes_for: proposal.votes_for,
-            votes_against: proposal.votes_against,
+        require!(ctx.accounts.property.is_active, ErrorCode::PropertyNotActive);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(price_per_token > 0, ErrorCode::InvalidTokenPrice);
+        if let Some(expiry) = expires_at {
+            require!(expiry > current_time, ErrorCode::InvalidListingExpiry);
+        }
+        ctx.accounts.seller_investor_record.escrow_tokens(amount, current_time)?;
+
+        let total_price = amount.checked_mul(price_per_token).ok_or(ErrorCode::MathOverflow)?;
+
+        // Move the listed tokens from the seller into the listing escrow
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let market_listing = &mut ctx.accounts.market_listing;
+        market_listing.seller = ctx.accounts.seller.key();
+        market_listing.property = ctx.accounts.property.key();
+        market_listing.amount = amount;
+        market_listing.price_per_token = price_per_token;
+        market_listing.total_price = total_price;
+        market_listing.is_active = true;
+        market_listing.created_at = current_time;
+        market_listing.market_price_reference = market_price_usd;
+        market_listing.expires_at = expires_at;
+        market_listing.price_in_usd = ctx.accounts.property.price_in_usd;
+
+        emit!(TokensListedForSale {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: ctx.accounts.seller.key(),
+            amount,
+            price_per_token,
+            market_price_reference: market_price_usd,
+            expires_at,
+            price_in_usd: ctx.accounts.property.price_in_usd,
         });
 
         Ok(())
     }
 
-    /// Transfer tokens between users
-    pub fn transfer_tokens(
-        ctx: Context<TransferTokens>,
+    /// Purchase tokens from secondary market, settling SOL and escrowed tokens atomically
+    pub fn buy_from_market(
+        ctx: Context<BuyFromMarket>,
         amount: u64,
     ) -> Result<()> {
-        let property = &ctx.accounts.property;
-        let from_record = &mut ctx.accounts.from_investor_record;
-        let to_record = &mut ctx.accounts.to_investor_record;
+        let market_listing = &ctx.accounts.market_listing;
         
+        require!(market_listing.is_active, ErrorCode::ListingNotActive);
+        require!(
+            !market_listing.is_expired(Clock::get()?.unix_timestamp),
+            ErrorCode::ListingExpired
+        );
         require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(from_record.tokens_owned >= amount, ErrorCode::InsufficientTokens);
+        require!(amount <= market_listing.amount, ErrorCode::InsufficientTokens);
+        require!(
+            ctx.accounts.buyer.key() != market_listing.seller,
+            ErrorCode::CannotBuyOwnListing
+        );
 
-        // Transfer SPL tokens
+        // Verify KYC status if required
+        if ctx.accounts.property.kyc_required {
+            require!(
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
+            );
+        }
+
+        let mut total_cost = amount
+            .checked_mul(market_listing.price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if market_listing.price_in_usd {
+            total_cost = ctx
+                .accounts
+                .platform_state
+                .usd_to_lamports(
+                    total_cost,
+                    Clock::get()?.unix_timestamp,
+                    ctx.accounts.sol_price_history.as_deref().map(|h| &**h),
+                )?;
+        }
+        let proceeds = TradeProceeds::split(
+            total_cost,
+            ctx.accounts.platform_state.trading_fee,
+            ctx.accounts.property.royalty_bps,
+        )?;
+
+        // Pay the seller, platform and property owner in SOL
+        pay_trade_from_wallet(
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.property_owner.to_account_info(),
+            &proceeds,
+        )?;
+        ctx.accounts.treasury.total_collected = ctx
+            .accounts
+            .treasury
+            .total_collected
+            .checked_add(proceeds.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Release escrowed tokens to the buyer
+        let listing_key = ctx.accounts.market_listing.key();
+        let escrow_seeds: &[&[u8]] = &[
+            b"escrow",
+            listing_key.as_ref(),
+            &[ctx.bumps.escrow_token_account],
+        ];
+        let signer_seeds = &[escrow_seeds];
         let cpi_accounts = Transfer {
-            from: ctx.accounts.from_token_account.to_account_info(),
-            to: ctx.accounts.to_token_account.to_account_info(),
-            authority: ctx.accounts.from.to_account_info(),
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_token_account.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
         token::transfer(cpi_ctx, amount)?;
 
         // Update investor records
-        from_record.tokens_owned -= amount;
-        to_record.tokens_owned += amount;
+        let property_key = ctx.accounts.property.key();
+        let seller_record = &mut ctx.accounts.seller_investor_record;
+        seller_record.settle_income(ctx.accounts.property.income_per_token)?;
+        seller_record.release_escrowed(amount)?;
+        seller_record.tokens_owned = seller_record
+            .tokens_owned
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientTokens)?;
+
+        let buyer_record = &mut ctx.accounts.buyer_investor_record;
+        buyer_record.investor = ctx.accounts.buyer.key();
+        buyer_record.property = property_key;
+        buyer_record.settle_income(ctx.accounts.property.income_per_token)?;
+        buyer_record.tokens_owned = buyer_record
+            .tokens_owned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        buyer_record.total_invested = buyer_record
+            .total_invested
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
 
-        emit!(TokensTransferred {
-            property_id: property.property_id.clone(),
-            from: ctx.accounts.from.key(),
-            to: ctx.accounts.to.key(),
+        // Update market listing
+        let market_listing = &mut ctx.accounts.market_listing;
+        market_listing.amount -= amount;
+        market_listing.total_price = market_listing
+            .amount
+            .checked_mul(market_listing.price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if market_listing.amount == 0 {
+            market_listing.is_active = false;
+        }
+
+        emit!(TokensPurchasedFromMarket {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: market_listing.seller,
+            buyer: ctx.accounts.buyer.key(),
             amount,
+            total_cost,
+        });
+
+        emit!(TradingFeesCollected {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: market_listing.seller,
+            buyer: ctx.accounts.buyer.key(),
+            platform_fee: proceeds.platform_fee,
+            royalty: proceeds.royalty,
         });
 
         Ok(())
     }
 
-    /// Update KYC status
-    pub fn update_kyc_status(
-        ctx: Context<UpdateKycStatus>,
-        user: Pubkey,
-        is_verified: bool,
-    ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
-            ErrorCode::Unauthorized
-        );
-
-        let kyc_record = &mut ctx.accounts.kyc_record;
-        kyc_record.user = user;
-        kyc_record.is_verified = is_verified;
-        kyc_record.updated_at = Clock::get()?.unix_timestamp;
+    /// Cancel a market listing, returning escrowed tokens and reclaiming rent
+    pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+        let listing_key = ctx.accounts.market_listing.key();
+        let amount_returned = release_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.seller.to_account_info(),
+            listing_key,
+            ctx.bumps.escrow_token_account,
+        )?;
+        ctx.accounts.seller_investor_record.release_escrowed(amount_returned)?;
 
-        emit!(KycStatusUpdated {
-            user,
-            is_verified,
-            updated_at: kyc_record.updated_at,
+        emit!(ListingCancelled {
+            property_id: ctx.accounts.property.property_id.clone(),
+            listing: listing_key,
+            seller: ctx.accounts.seller.key(),
+            amount_returned,
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Update SOL/USD price using Chainlink price feeds
-    pub fn update_sol_price(
-        ctx: Context<UpdateSolPrice>,
-        new_price: u64, // Price in USD with 8 decimals (e.g., 10000000000 = $100.00)
-        chainlink_round_id: u64,
-    ) -> Result<()> {
+    /// Close an expired listing and return escrowed tokens to the seller (permissionless crank)
+    pub fn close_expired_listing(ctx: Context<CloseExpiredListing>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
-            ErrorCode::Unauthorized
+            ctx.accounts.market_listing.is_expired(current_time),
+            ErrorCode::ListingNotExpired
         );
-        
-        let platform_state = &mut ctx.accounts.platform_state;
-        platform_state.sol_usd_price = new_price;
-        platform_state.last_price_update = Clock::get()?.unix_timestamp;
-        
-        emit!(SolPriceUpdated {
-            new_price,
-            chainlink_round_id,
-            timestamp: Clock::get()?.unix_timestamp,
+
+        let listing_key = ctx.accounts.market_listing.key();
+        let amount_returned = release_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.seller.to_account_info(),
+            listing_key,
+            ctx.bumps.escrow_token_account,
+        )?;
+        ctx.accounts.seller_investor_record.release_escrowed(amount_returned)?;
+
+        emit!(ListingExpired {
+            property_id: ctx.accounts.property.property_id.clone(),
+            listing: listing_key,
+            seller: ctx.accounts.seller.key(),
+            amount_returned,
+            closed_by: ctx.accounts.cranker.key(),
+            timestamp: current_time,
         });
-        
+
         Ok(())
     }
 
-    /// Distribute rental income to token holders (individual)
-    pub fn distribute_rental_income(
-        ctx: Context<DistributeRentalIncome>,
-        total_income: u64,
-        chainlink_round_id: u64,
+    /// Amend the price and/or amount of an active market listing
+    pub fn update_listing(
+        ctx: Context<UpdateListing>,
+        new_amount: u64,
+        new_price_per_token: u64,
     ) -> Result<()> {
-        let property = &mut ctx.accounts.property;
-        let platform_state = &ctx.accounts.platform_state;
-        
-        require!(
-            ctx.accounts.authority.key() == property.owner ||
-            ctx.accounts.authority.key() == platform_state.authority,
-            ErrorCode::Unauthorized
-        );
-        
-        require!(total_income > 0, ErrorCode::InvalidAmount);
-        require!(property.tokens_sold > 0, ErrorCode::NoTokensIssued);
+        let market_listing = &ctx.accounts.market_listing;
 
-        // Calculate platform fee
-        let platform_fee = total_income
-            .checked_mul(platform_state.platform_fee)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(10000)
+        require!(market_listing.is_active, ErrorCode::ListingNotActive);
+        require!(new_amount > 0, ErrorCode::InvalidAmount);
+        require!(new_price_per_token > 0, ErrorCode::InvalidTokenPrice);
+        let old_amount = market_listing.amount;
+        let old_price_per_token = market_listing.price_per_token;
+        let total_price = new_amount
+            .checked_mul(new_price_per_token)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        let distributable_income = total_income
-            .checked_sub(platform_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
+        if new_amount > old_amount {
+            // Top up the escrow from the seller
+            ctx.accounts
+                .seller_investor_record
+                .escrow_tokens(new_amount - old_amount, Clock::get()?.unix_timestamp)?;
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.seller_token_account.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.seller.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token::transfer(cpi_ctx, new_amount - old_amount)?;
+        } else if new_amount < old_amount {
+            // Return the excess from the escrow to the seller
+            let listing_key = ctx.accounts.market_listing.key();
+            let escrow_seeds: &[&[u8]] = &[
+                b"escrow",
+                listing_key.as_ref(),
+                &[ctx.bumps.escrow_token_account],
+            ];
+            let signer_seeds = &[escrow_seeds];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.seller_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_token_account.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+            token::transfer(cpi_ctx, old_amount - new_amount)?;
+            ctx.accounts
+                .seller_investor_record
+                .release_escrowed(old_amount - new_amount)?;
+        }
 
-        property.total_rental_income += distributable_income;
-        property.last_income_distribution = Clock::get()?.unix_timestamp;
+        let market_listing = &mut ctx.accounts.market_listing;
+        market_listing.amount = new_amount;
+        market_listing.price_per_token = new_price_per_token;
+        market_listing.total_price = total_price;
 
-        emit!(RentalIncomeDistributed {
-            property_id: property.property_id.clone(),
-            total_income,
-            platform_fee,
-            distributable_income,
-            chainlink_round_id,
+        emit!(ListingUpdated {
+            property_id: ctx.accounts.property.property_id.clone(),
+            listing: market_listing.key(),
+            seller: ctx.accounts.seller.key(),
+            old_amount,
+            new_amount,
+            old_price_per_token,
+            new_price_per_token,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Claim rental income for an investor (individual)
-    pub fn claim_rental_income(ctx: Context<ClaimRentalIncome>) -> Result<()> {
+    /// Place a bid for property tokens, locking SOL in the bid escrow account
+    pub fn place_bid(
+        ctx: Context<PlaceBid>,
+        bid_id: u64,
+        amount: u64,
+        price_per_token: u64, // Maximum price per token in lamports
+    ) -> Result<()> {
         let property = &ctx.accounts.property;
-        let investor_record = &mut ctx.accounts.investor_record;
-        
-        require!(investor_record.tokens_owned > 0, ErrorCode::NoTokensOwned);
-        
-        // Calculate claimable amount
-        let ownership_percentage = (investor_record.tokens_owned as u128)
-            .checked_mul(10000u128)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(property.tokens_sold as u128)
-            .ok_or(ErrorCode::MathOverflow)? as u64;
 
-        let claimable_amount = property.total_rental_income
-            .checked_mul(ownership_percentage)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_sub(investor_record.total_claimed)
-            .ok_or(ErrorCode::MathOverflow)?;
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(price_per_token > 0, ErrorCode::InvalidTokenPrice);
 
-        require!(claimable_amount > 0, ErrorCode::NothingToClaim);
+        // Verify KYC status if required
+        if property.kyc_required {
+            require!(
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
+            );
+        }
 
-        // Transfer SOL from property vault to investor
-        **ctx.accounts.property_vault.to_account_info().try_borrow_mut_lamports()? -= claimable_amount;
-        **ctx.accounts.investor.to_account_info().try_borrow_mut_lamports()? += claimable_amount;
+        let total_cost = amount
+            .checked_mul(price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
 
-        investor_record.total_claimed += claimable_amount;
-        investor_record.last_claim_time = Clock::get()?.unix_timestamp;
+        // Lock the bid's SOL in the bid account itself
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.bidder.key(),
+            &ctx.accounts.market_bid.key(),
+            total_cost,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.bidder.to_account_info(),
+                ctx.accounts.market_bid.to_account_info(),
+            ],
+        )?;
 
-        emit!(RentalIncomeClaimed {
-            property_id: property.property_id.clone(),
-            investor: ctx.accounts.investor.key(),
-            amount: claimable_amount,
-            total_claimed: investor_record.total_claimed,
+        let market_bid = &mut ctx.accounts.market_bid;
+        market_bid.bidder = ctx.accounts.bidder.key();
+        market_bid.property = ctx.accounts.property.key();
+        market_bid.bid_id = bid_id;
+        market_bid.amount = amount;
+        market_bid.price_per_token = price_per_token;
+        market_bid.is_active = true;
+        market_bid.created_at = Clock::get()?.unix_timestamp;
+        market_bid.bump = ctx.bumps.market_bid;
+
+        emit!(BidPlaced {
+            property_id: ctx.accounts.property.property_id.clone(),
+            bid: market_bid.key(),
+            bidder: ctx.accounts.bidder.key(),
+            amount,
+            price_per_token,
+            total_escrowed: total_cost,
         });
 
         Ok(())
     }
-}
 
-// Account structures - simplified to reduce stack usage
-#[account]
-pub struct PlatformState {
-    pub authority: Pubkey,
-    pub platform_fee: u64,
-    pub governance_threshold: u64,
-    pub total_properties: u64,
-    pub total_value_locked: u64,
-    pub sol_usd_price: u64,
-    pub last_price_update: i64,
-}
+    /// Fill a bid (partially or fully) by selling tokens to the bidder
+    pub fn fill_bid(
+        ctx: Context<FillBid>,
+        amount: u64,
+    ) -> Result<()> {
+        let market_bid = &ctx.accounts.market_bid;
 
-#[account]
-pub struct Property {
-    pub property_id: String,        // 32 max
-    pub owner: Pubkey,
-    pub total_tokens: u64,
-    pub tokens_sold: u64,
-    pub token_price: u64,
-    pub property_address: String,   // 100 max
-    pub property_type: PropertyType,
-    pub legal_document_hash: String, // 32 max
-    pub total_rental_income: u64,
-    pub last_income_distribution: i64,
-    pub is_active: bool,
-    pub token_mint: Pubkey,
-    pub property_valuation: u64,
-    pub last_valuation_update: i64,
-    pub kyc_required: bool,
-    pub expected_rental_yield: u64,
-    pub property_vault: Pubkey,
+        require!(market_bid.is_active, ErrorCode::BidNotActive);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount <= market_bid.amount, ErrorCode::InsufficientTokens);
+        require!(
+            ctx.accounts.seller.key() != market_bid.bidder,
+            ErrorCode::CannotFillOwnBid
+        );
+        ctx.accounts.seller_investor_record.require_unlocked(amount, Clock::get()?.unix_timestamp)?;
+
+        let total_cost = amount
+            .checked_mul(market_bid.price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let proceeds = TradeProceeds::split(
+            total_cost,
+            ctx.accounts.platform_state.trading_fee,
+            ctx.accounts.property.royalty_bps,
+        )?;
+
+        // Transfer tokens from seller to bidder
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.bidder_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        // Release escrowed SOL to the seller, platform and property owner
+        pay_trade_from_escrow(
+            &ctx.accounts.market_bid.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.property_owner.to_account_info(),
+            &proceeds,
+        )?;
+        ctx.accounts.treasury.total_collected = ctx
+            .accounts
+            .treasury
+            .total_collected
+            .checked_add(proceeds.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update investor records
+        let property_key = ctx.accounts.property.key();
+        let seller_record = &mut ctx.accounts.seller_investor_record;
+        seller_record.settle_income(ctx.accounts.property.income_per_token)?;
+        seller_record.tokens_owned -= amount;
+
+        let bidder_record = &mut ctx.accounts.bidder_investor_record;
+        bidder_record.investor = ctx.accounts.bidder.key();
+        bidder_record.property = property_key;
+        bidder_record.settle_income(ctx.accounts.property.income_per_token)?;
+        bidder_record.tokens_owned = bidder_record
+            .tokens_owned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        bidder_record.total_invested = bidder_record
+            .total_invested
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update bid
+        let market_bid = &mut ctx.accounts.market_bid;
+        market_bid.amount -= amount;
+        if market_bid.amount == 0 {
+            market_bid.is_active = false;
+        }
+
+        emit!(BidFilled {
+            property_id: ctx.accounts.property.property_id.clone(),
+            bid: market_bid.key(),
+            bidder: market_bid.bidder,
+            seller: ctx.accounts.seller.key(),
+            amount,
+            total_cost,
+            amount_remaining: market_bid.amount,
+        });
+
+        emit!(TradingFeesCollected {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: ctx.accounts.seller.key(),
+            buyer: market_bid.bidder,
+            platform_fee: proceeds.platform_fee,
+            royalty: proceeds.royalty,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a bid, refunding the remaining escrowed SOL and closing the account
+    pub fn cancel_bid(ctx: Context<CancelBid>) -> Result<()> {
+        let market_bid = &ctx.accounts.market_bid;
+        let amount_refunded = market_bid
+            .amount
+            .checked_mul(market_bid.price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(BidCancelled {
+            property_id: ctx.accounts.property.property_id.clone(),
+            bid: market_bid.key(),
+            bidder: ctx.accounts.bidder.key(),
+            amount_cancelled: market_bid.amount,
+            amount_refunded,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Create the central limit order book for a property
+    pub fn initialize_order_book(ctx: Context<InitializeOrderBook>) -> Result<()> {
+        let property = &ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let order_book = &mut ctx.accounts.order_book;
+        order_book.property = property.key();
+        order_book.token_mint = property.token_mint;
+        order_book.next_order_id = 0;
+        order_book.bids = Vec::new();
+        order_book.asks = Vec::new();
+        order_book.bump = ctx.bumps.order_book;
+
+        emit!(OrderBookInitialized {
+            property_id: property.property_id.clone(),
+            order_book: order_book.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Place a limit order on a property's order book, escrowing tokens (asks) or SOL (bids)
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: OrderSide,
+        price: u64, // Price per token in lamports
+        amount: u64,
+    ) -> Result<()> {
+        let property = &ctx.accounts.property;
+
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(price > 0, ErrorCode::InvalidTokenPrice);
+
+        // Verify KYC status if required
+        if property.kyc_required {
+            require!(
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
+            );
+        }
+
+        let owner = ctx.accounts.owner.key();
+        require!(
+            !ctx.accounts.order_book.would_self_trade(&side, owner, price),
+            ErrorCode::SelfTrade
+        );
+
+        match side {
+            OrderSide::Bid => {
+                let total_cost = amount
+                    .checked_mul(price)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                // Lock SOL in the order book account
+                let ix = anchor_lang::solana_program::system_instruction::transfer(
+                    &owner,
+                    &ctx.accounts.order_book.key(),
+                    total_cost,
+                );
+                anchor_lang::solana_program::program::invoke(
+                    &ix,
+                    &[
+                        ctx.accounts.owner.to_account_info(),
+                        ctx.accounts.order_book.to_account_info(),
+                    ],
+                )?;
+            }
+            OrderSide::Ask => {
+                let investor_record = ctx
+                    .accounts
+                    .investor_record
+                    .as_mut()
+                    .ok_or(ErrorCode::NoTokensOwned)?;
+                investor_record.escrow_tokens(amount, Clock::get()?.unix_timestamp)?;
+                let owner_token_account = ctx
+                    .accounts
+                    .owner_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+
+                // Lock tokens in the order book vault
+                let cpi_accounts = Transfer {
+                    from: owner_token_account.to_account_info(),
+                    to: ctx.accounts.order_book_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token::transfer(cpi_ctx, amount)?;
+            }
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let order_book = &mut ctx.accounts.order_book;
+        let order_id = order_book.next_order_id;
+        order_book.insert_order(
+            &side,
+            Order {
+                order_id,
+                owner,
+                price,
+                amount,
+                created_at: current_time,
+            },
+        )?;
+        order_book.next_order_id = order_id
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(OrderPlaced {
+            property_id: ctx.accounts.property.property_id.clone(),
+            order_id,
+            owner,
+            side,
+            price,
+            amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a resting order, refunding its escrowed tokens or SOL
+    pub fn cancel_order(
+        ctx: Context<CancelOrder>,
+        order_id: u64,
+    ) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let (side, order) = ctx
+            .accounts
+            .order_book
+            .remove_order(order_id)
+            .ok_or(ErrorCode::OrderNotFound)?;
+        require!(order.owner == owner, ErrorCode::Unauthorized);
+
+        match side {
+            OrderSide::Bid => {
+                let refund = order
+                    .amount
+                    .checked_mul(order.price)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                **ctx.accounts.order_book.to_account_info().try_borrow_mut_lamports()? -= refund;
+                **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += refund;
+            }
+            OrderSide::Ask => {
+                let owner_token_account = ctx
+                    .accounts
+                    .owner_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let order_book_key = ctx.accounts.order_book.key();
+                let vault_seeds: &[&[u8]] = &[
+                    b"order_book_vault",
+                    order_book_key.as_ref(),
+                    &[ctx.bumps.order_book_vault],
+                ];
+                let signer_seeds = &[vault_seeds];
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.order_book_vault.to_account_info(),
+                    to: owner_token_account.to_account_info(),
+                    authority: ctx.accounts.order_book_vault.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+                token::transfer(cpi_ctx, order.amount)?;
+                ctx.accounts
+                    .investor_record
+                    .as_mut()
+                    .ok_or(ErrorCode::InvalidInvestorRecord)?
+                    .release_escrowed(order.amount)?;
+            }
+        }
+
+        emit!(OrderCancelled {
+            property_id: ctx.accounts.property.property_id.clone(),
+            order_id,
+            owner,
+            side,
+            amount_cancelled: order.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Match the best bid against the best ask (permissionless crank, one fill per call)
+    pub fn match_orders(ctx: Context<MatchOrders>) -> Result<()> {
+        let order_book = &ctx.accounts.order_book;
+        let best_bid = order_book.bids.first().cloned().ok_or(ErrorCode::NoMatchingOrders)?;
+        let best_ask = order_book.asks.first().cloned().ok_or(ErrorCode::NoMatchingOrders)?;
+
+        require!(best_bid.price >= best_ask.price, ErrorCode::NoMatchingOrders);
+        require!(ctx.accounts.buyer.key() == best_bid.owner, ErrorCode::InvalidOrderOwner);
+        require!(ctx.accounts.seller.key() == best_ask.owner, ErrorCode::InvalidOrderOwner);
+
+        // The resting (older) order sets the execution price
+        let fill_amount = best_bid.amount.min(best_ask.amount);
+        let fill_price = if best_bid.order_id < best_ask.order_id {
+            best_bid.price
+        } else {
+            best_ask.price
+        };
+        let total_cost = fill_amount
+            .checked_mul(fill_price)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let buyer_refund = fill_amount
+            .checked_mul(best_bid.price - fill_price)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let proceeds = TradeProceeds::split(
+            total_cost,
+            ctx.accounts.platform_state.trading_fee,
+            ctx.accounts.property.royalty_bps,
+        )?;
+
+        // Release escrowed tokens to the buyer
+        let order_book_key = ctx.accounts.order_book.key();
+        let vault_seeds: &[&[u8]] = &[
+            b"order_book_vault",
+            order_book_key.as_ref(),
+            &[ctx.bumps.order_book_vault],
+        ];
+        let signer_seeds = &[vault_seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.order_book_vault.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.order_book_vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, fill_amount)?;
+
+        // Release escrowed SOL to the seller, platform and property owner, and refund any
+        // price improvement to the buyer
+        pay_trade_from_escrow(
+            &ctx.accounts.order_book.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.property_owner.to_account_info(),
+            &proceeds,
+        )?;
+        **ctx.accounts.order_book.to_account_info().try_borrow_mut_lamports()? -= buyer_refund;
+        **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? += buyer_refund;
+        ctx.accounts.treasury.total_collected = ctx
+            .accounts
+            .treasury
+            .total_collected
+            .checked_add(proceeds.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update investor records
+        let property_key = ctx.accounts.property.key();
+        let seller_record = &mut ctx.accounts.seller_investor_record;
+        seller_record.settle_income(ctx.accounts.property.income_per_token)?;
+        seller_record.release_escrowed(fill_amount)?;
+        seller_record.tokens_owned = seller_record
+            .tokens_owned
+            .checked_sub(fill_amount)
+            .ok_or(ErrorCode::InsufficientTokens)?;
+
+        let buyer_record = &mut ctx.accounts.buyer_investor_record;
+        buyer_record.investor = best_bid.owner;
+        buyer_record.property = property_key;
+        buyer_record.settle_income(ctx.accounts.property.income_per_token)?;
+        buyer_record.tokens_owned = buyer_record
+            .tokens_owned
+            .checked_add(fill_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        buyer_record.total_invested = buyer_record
+            .total_invested
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update the book
+        let order_book = &mut ctx.accounts.order_book;
+        order_book.bids[0].amount -= fill_amount;
+        if order_book.bids[0].amount == 0 {
+            order_book.bids.remove(0);
+        }
+        order_book.asks[0].amount -= fill_amount;
+        if order_book.asks[0].amount == 0 {
+            order_book.asks.remove(0);
+        }
+
+        emit!(OrdersMatched {
+            property_id: ctx.accounts.property.property_id.clone(),
+            bid_order_id: best_bid.order_id,
+            ask_order_id: best_ask.order_id,
+            buyer: best_bid.owner,
+            seller: best_ask.owner,
+            price: fill_price,
+            amount: fill_amount,
+            total_cost,
+        });
+
+        emit!(TradingFeesCollected {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: best_ask.owner,
+            buyer: best_bid.owner,
+            platform_fee: proceeds.platform_fee,
+            royalty: proceeds.royalty,
+        });
+
+        Ok(())
+    }
+
+    /// Create an English (ascending) or Dutch (descending) auction for a block of tokens
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        auction_id: u64,
+        auction_type: AuctionType,
+        amount: u64,
+        start_price: u64, // Per token: minimum opening bid (English) or starting price (Dutch)
+        reserve_price: u64, // Per token: floor price reached at end_time (Dutch only)
+        duration: i64,
+    ) -> Result<()> {
+        require!(ctx.accounts.property.is_active, ErrorCode::PropertyNotActive);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(start_price > 0, ErrorCode::InvalidTokenPrice);
+        require!(duration > 0, ErrorCode::InvalidAuctionDuration);
+        if auction_type == AuctionType::Dutch {
+            require!(
+                reserve_price > 0 && reserve_price <= start_price,
+                ErrorCode::InvalidTokenPrice
+            );
+        }
+        ctx.accounts.seller_investor_record.escrow_tokens(amount, Clock::get()?.unix_timestamp)?;
+
+        // Move the auctioned block into the auction escrow
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.auction;
+        auction.seller = ctx.accounts.seller.key();
+        auction.property = ctx.accounts.property.key();
+        auction.auction_id = auction_id;
+        auction.auction_type = auction_type.clone();
+        auction.amount = amount;
+        auction.start_price = start_price;
+        auction.reserve_price = reserve_price;
+        auction.start_time = current_time;
+        auction.end_time = current_time
+            .checked_add(duration)
+            .ok_or(ErrorCode::MathOverflow)?;
+        auction.highest_bidder = None;
+        auction.highest_bid = 0;
+        auction.is_settled = false;
+        auction.bump = ctx.bumps.auction;
+
+        emit!(AuctionCreated {
+            property_id: ctx.accounts.property.property_id.clone(),
+            auction: auction.key(),
+            seller: auction.seller,
+            auction_type,
+            amount,
+            start_price,
+            reserve_price,
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Bid on an English auction, escrowing SOL and refunding the previous highest bidder
+    pub fn place_auction_bid(
+        ctx: Context<PlaceAuctionBid>,
+        price_per_token: u64,
+    ) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(auction.auction_type == AuctionType::English, ErrorCode::InvalidAuctionType);
+        require!(!auction.is_settled, ErrorCode::AuctionSettled);
+        require!(current_time < auction.end_time, ErrorCode::AuctionEnded);
+        require!(price_per_token >= auction.start_price, ErrorCode::BidTooLow);
+        require!(price_per_token > auction.highest_bid, ErrorCode::BidTooLow);
+        require!(
+            ctx.accounts.bidder.key() != auction.seller,
+            ErrorCode::CannotBuyOwnListing
+        );
+
+        // Verify KYC status if required
+        if ctx.accounts.property.kyc_required {
+            require!(
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
+            );
+        }
+
+        let total_bid = auction
+            .amount
+            .checked_mul(price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Refund the bidder being outbid
+        if let Some(previous_bidder) = auction.highest_bidder {
+            let previous_bidder_info = ctx
+                .accounts
+                .previous_bidder
+                .as_ref()
+                .ok_or(ErrorCode::InvalidPreviousBidder)?;
+            require!(
+                previous_bidder_info.key() == previous_bidder,
+                ErrorCode::InvalidPreviousBidder
+            );
+            let refund = auction
+                .amount
+                .checked_mul(auction.highest_bid)
+                .ok_or(ErrorCode::MathOverflow)?;
+            **ctx.accounts.auction.to_account_info().try_borrow_mut_lamports()? -= refund;
+            **previous_bidder_info.to_account_info().try_borrow_mut_lamports()? += refund;
+        }
+
+        // Escrow the new bid in the auction account
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.bidder.key(),
+            &ctx.accounts.auction.key(),
+            total_bid,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.bidder.to_account_info(),
+                ctx.accounts.auction.to_account_info(),
+            ],
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.highest_bidder = Some(ctx.accounts.bidder.key());
+        auction.highest_bid = price_per_token;
+
+        emit!(AuctionBidPlaced {
+            property_id: ctx.accounts.property.property_id.clone(),
+            auction: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            price_per_token,
+            total_bid,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Buy the whole block of a Dutch auction at the current declining price
+    pub fn buy_dutch_auction(ctx: Context<BuyDutchAuction>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(auction.auction_type == AuctionType::Dutch, ErrorCode::InvalidAuctionType);
+        require!(!auction.is_settled, ErrorCode::AuctionSettled);
+        require!(current_time < auction.end_time, ErrorCode::AuctionEnded);
+        require!(
+            ctx.accounts.buyer.key() != auction.seller,
+            ErrorCode::CannotBuyOwnListing
+        );
+
+        // Verify KYC status if required
+        if ctx.accounts.property.kyc_required {
+            require!(
+                ctx.accounts.kyc_record.is_verified,
+                ErrorCode::KycNotVerified
+            );
+        }
+
+        let amount = auction.amount;
+        let price_per_token = auction.current_price(current_time)?;
+        let total_cost = amount
+            .checked_mul(price_per_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let proceeds = TradeProceeds::split(
+            total_cost,
+            ctx.accounts.platform_state.trading_fee,
+            ctx.accounts.property.royalty_bps,
+        )?;
+
+        // Pay the seller, platform and property owner in SOL
+        pay_trade_from_wallet(
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.property_owner.to_account_info(),
+            &proceeds,
+        )?;
+        ctx.accounts.treasury.total_collected = ctx
+            .accounts
+            .treasury
+            .total_collected
+            .checked_add(proceeds.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Release the block to the buyer and close the escrow
+        release_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.buyer_token_account,
+            &ctx.accounts.seller.to_account_info(),
+            ctx.accounts.auction.key(),
+            ctx.bumps.escrow_token_account,
+        )?;
+
+        // Update investor records
+        let property_key = ctx.accounts.property.key();
+        let seller_record = &mut ctx.accounts.seller_investor_record;
+        seller_record.settle_income(ctx.accounts.property.income_per_token)?;
+        seller_record.release_escrowed(amount)?;
+        seller_record.tokens_owned = seller_record
+            .tokens_owned
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientTokens)?;
+
+        let buyer_record = &mut ctx.accounts.buyer_investor_record;
+        buyer_record.investor = ctx.accounts.buyer.key();
+        buyer_record.property = property_key;
+        buyer_record.settle_income(ctx.accounts.property.income_per_token)?;
+        buyer_record.tokens_owned = buyer_record
+            .tokens_owned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        buyer_record.total_invested = buyer_record
+            .total_invested
+            .checked_add(total_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        ctx.accounts.auction.is_settled = true;
+
+        emit!(TokensPurchasedFromMarket {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: ctx.accounts.seller.key(),
+            buyer: ctx.accounts.buyer.key(),
+            amount,
+            total_cost,
+        });
+
+        emit!(TradingFeesCollected {
+            property_id: ctx.accounts.property.property_id.clone(),
+            seller: ctx.accounts.seller.key(),
+            buyer: ctx.accounts.buyer.key(),
+            platform_fee: proceeds.platform_fee,
+            royalty: proceeds.royalty,
+        });
+
+        emit!(AuctionSettled {
+            property_id: ctx.accounts.property.property_id.clone(),
+            auction: ctx.accounts.auction.key(),
+            seller: ctx.accounts.seller.key(),
+            winner: Some(ctx.accounts.buyer.key()),
+            amount,
+            price_per_token,
+            total_cost,
+        });
+
+        Ok(())
+    }
+
+    /// Settle an ended auction (permissionless): pay the seller and deliver tokens to the
+    /// winner, or return the block to the seller if there was no winning bid
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!auction.is_settled, ErrorCode::AuctionSettled);
+        require!(current_time >= auction.end_time, ErrorCode::AuctionNotEnded);
+
+        let auction_key = auction.key();
+        let amount = auction.amount;
+        let price_per_token = auction.highest_bid;
+
+        let (winner, total_cost) = match auction.highest_bidder {
+            Some(winner) => {
+                let winner_info = ctx
+                    .accounts
+                    .winner
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidAuctionWinner)?;
+                require!(winner_info.key() == winner, ErrorCode::InvalidAuctionWinner);
+                let winner_token_account = ctx
+                    .accounts
+                    .winner_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+
+                let total_cost = amount
+                    .checked_mul(price_per_token)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                let proceeds = TradeProceeds::split(
+                    total_cost,
+                    ctx.accounts.platform_state.trading_fee,
+                    ctx.accounts.property.royalty_bps,
+                )?;
+
+                // Deliver the block to the winner
+                release_escrow(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.escrow_token_account,
+                    winner_token_account,
+                    &ctx.accounts.seller.to_account_info(),
+                    auction_key,
+                    ctx.bumps.escrow_token_account,
+                )?;
+
+                // Pay the seller, platform and property owner from the escrowed winning bid
+                pay_trade_from_escrow(
+                    &ctx.accounts.auction.to_account_info(),
+                    &ctx.accounts.seller.to_account_info(),
+                    &ctx.accounts.treasury.to_account_info(),
+                    &ctx.accounts.property_owner.to_account_info(),
+                    &proceeds,
+                )?;
+                ctx.accounts.treasury.total_collected = ctx
+                    .accounts
+                    .treasury
+                    .total_collected
+                    .checked_add(proceeds.platform_fee)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                // Update investor records
+                let property_key = ctx.accounts.property.key();
+                let seller_record = &mut ctx.accounts.seller_investor_record;
+                seller_record.settle_income(ctx.accounts.property.income_per_token)?;
+                seller_record.release_escrowed(amount)?;
+                seller_record.tokens_owned = seller_record
+                    .tokens_owned
+                    .checked_sub(amount)
+                    .ok_or(ErrorCode::InsufficientTokens)?;
+
+                let winner_record = ctx
+                    .accounts
+                    .winner_investor_record
+                    .as_mut()
+                    .ok_or(ErrorCode::InvalidInvestorRecord)?;
+                winner_record.investor = winner;
+                winner_record.property = property_key;
+                winner_record.settle_income(ctx.accounts.property.income_per_token)?;
+                winner_record.tokens_owned = winner_record
+                    .tokens_owned
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                winner_record.total_invested = winner_record
+                    .total_invested
+                    .checked_add(total_cost)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                emit!(TokensPurchasedFromMarket {
+                    property_id: ctx.accounts.property.property_id.clone(),
+                    seller: ctx.accounts.seller.key(),
+                    buyer: winner,
+                    amount,
+                    total_cost,
+                });
+
+                emit!(TradingFeesCollected {
+                    property_id: ctx.accounts.property.property_id.clone(),
+                    seller: ctx.accounts.seller.key(),
+                    buyer: winner,
+                    platform_fee: proceeds.platform_fee,
+                    royalty: proceeds.royalty,
+                });
+
+                (Some(winner), total_cost)
+            }
+            None => {
+                // No winning bid: return the block to the seller
+                release_escrow(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.escrow_token_account,
+                    &ctx.accounts.seller_token_account,
+                    &ctx.accounts.seller.to_account_info(),
+                    auction_key,
+                    ctx.bumps.escrow_token_account,
+                )?;
+                ctx.accounts.seller_investor_record.release_escrowed(amount)?;
+                (None, 0)
+            }
+        };
+
+        ctx.accounts.auction.is_settled = true;
+
+        emit!(AuctionSettled {
+            property_id: ctx.accounts.property.property_id.clone(),
+            auction: auction_key,
+            seller: ctx.accounts.seller.key(),
+            winner,
+            amount,
+            price_per_token,
+            total_cost,
+        });
+
+        Ok(())
+    }
+
+    /// Create a constant-product liquidity pool pairing a property token with a quote mint
+    /// (wrapped SOL or a stablecoin)
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        fee_bps: u64, // Swap fee in basis points
+        platform_fee_share_bps: u64, // Share of the swap fee kept for the platform, in basis points
+    ) -> Result<()> {
+        let property = &ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(fee_bps <= 10000, ErrorCode::InvalidFee);
+        require!(platform_fee_share_bps <= 10000, ErrorCode::InvalidFee);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.property = property.key();
+        pool.token_mint = ctx.accounts.token_mint.key();
+        pool.quote_mint = ctx.accounts.quote_mint.key();
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.token_vault = ctx.accounts.token_vault.key();
+        pool.quote_vault = ctx.accounts.quote_vault.key();
+        pool.fee_bps = fee_bps;
+        pool.platform_fee_share_bps = platform_fee_share_bps;
+        pool.platform_fees_token = 0;
+        pool.platform_fees_quote = 0;
+        pool.income_per_lp_share = 0;
+        pool.income_indexed = 0;
+        pool.bump = ctx.bumps.pool;
+
+        // Pooled tokens earn rental income on the pool's own record
+        let pool_record = &mut ctx.accounts.pool_record;
+        pool_record.investor = pool.key();
+        pool_record.property = property.key();
+        pool_record.income_checkpoint = property.income_per_token;
+
+        emit!(PoolInitialized {
+            property_id: property.property_id.clone(),
+            pool: pool.key(),
+            token_mint: pool.token_mint,
+            quote_mint: pool.quote_mint,
+            fee_bps,
+            platform_fee_share_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Deposit property tokens and quote tokens into a pool in exchange for LP tokens
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        token_amount: u64,
+        max_quote_amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        require!(token_amount > 0 && max_quote_amount > 0, ErrorCode::InvalidAmount);
+        require!(ctx.accounts.kyc_record.is_verified, ErrorCode::KycNotVerified);
+        ctx.accounts.investor_record.require_unlocked(token_amount, Clock::get()?.unix_timestamp)?;
+
+        let pool = &ctx.accounts.pool;
+        let (token_reserve, quote_reserve) = pool.reserves(
+            ctx.accounts.token_vault.amount,
+            ctx.accounts.quote_vault.amount,
+        )?;
+        let lp_supply = ctx.accounts.lp_mint.supply;
+
+        let (quote_amount, lp_amount) = if lp_supply == 0 {
+            let lp_amount = integer_sqrt(
+                (token_amount as u128)
+                    .checked_mul(max_quote_amount as u128)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            ) as u64;
+            require!(
+                lp_amount > LiquidityPool::MINIMUM_LIQUIDITY,
+                ErrorCode::InsufficientLiquidity
+            );
+            (max_quote_amount, lp_amount - LiquidityPool::MINIMUM_LIQUIDITY)
+        } else {
+            require!(token_reserve > 0, ErrorCode::InsufficientLiquidity);
+            // Round the quote deposit up so the pool never loses value
+            let quote_amount = (token_amount as u128)
+                .checked_mul(quote_reserve as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                .div_ceil(token_reserve as u128) as u64;
+            let lp_amount = (token_amount as u128)
+                .checked_mul(lp_supply as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(token_reserve as u128)
+                .ok_or(ErrorCode::MathOverflow)? as u64;
+            (quote_amount, lp_amount)
+        };
+
+        require!(quote_amount <= max_quote_amount, ErrorCode::SlippageExceeded);
+        require!(lp_amount > 0 && lp_amount >= min_lp_out, ErrorCode::SlippageExceeded);
+
+        // Deposit both sides into the pool vaults
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.provider_token_account.to_account_info(),
+            to: ctx.accounts.token_vault.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        token::transfer(CpiContext::new(cpi_program.clone(), cpi_accounts), token_amount)?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.provider_quote_account.to_account_info(),
+            to: ctx.accounts.quote_vault.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        token::transfer(CpiContext::new(cpi_program.clone(), cpi_accounts), quote_amount)?;
+
+        // Settle pool income over the current LP supply before it changes
+        let income_per_token = ctx.accounts.property.income_per_token;
+        ctx.accounts.pool_record.settle_income(income_per_token)?;
+        ctx.accounts.pool.accrue_lp_income(&ctx.accounts.pool_record, lp_supply)?;
+        let lp_position = &mut ctx.accounts.lp_position;
+        lp_position.pool = ctx.accounts.pool.key();
+        lp_position.provider = ctx.accounts.provider.key();
+        lp_position.bump = ctx.bumps.lp_position;
+        lp_position.settle_income(
+            ctx.accounts.pool.income_per_lp_share,
+            ctx.accounts.provider_lp_account.amount,
+        )?;
+
+        // Mint LP tokens to the provider
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        let pool_seeds: &[&[u8]] = &[b"pool", token_mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+        let signer_seeds = &[pool_seeds];
+        if lp_supply == 0 {
+            // Lock the first MINIMUM_LIQUIDITY LP tokens for good so the LP share price can't be
+            // inflated by the first depositor
+            let cpi_accounts = MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.locked_lp_vault.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            token::mint_to(
+                CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds),
+                LiquidityPool::MINIMUM_LIQUIDITY,
+            )?;
+        }
+        if ctx.accounts.provider_lp_account.is_frozen() {
+            let cpi_accounts = ThawAccount {
+                account: ctx.accounts.provider_lp_account.to_account_info(),
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds))?;
+        }
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.provider_lp_account.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::mint_to(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds), lp_amount)?;
+        // LP tokens stay frozen so each provider's income follows its LP balance
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.provider_lp_account.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds))?;
+
+        // Pooled tokens move from the provider's income record to the pool's
+        let investor_record = &mut ctx.accounts.investor_record;
+        investor_record.settle_income(income_per_token)?;
+        investor_record.tokens_owned = investor_record
+            .tokens_owned
+            .checked_sub(token_amount)
+            .ok_or(ErrorCode::InsufficientTokens)?;
+        let pool_record = &mut ctx.accounts.pool_record;
+        pool_record.tokens_owned = pool_record
+            .tokens_owned
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LiquidityAdded {
+            pool: ctx.accounts.pool.key(),
+            provider: ctx.accounts.provider.key(),
+            token_amount,
+            quote_amount,
+            lp_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Burn LP tokens and withdraw the provider's share of both pool reserves
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lp_amount: u64,
+        min_token_out: u64,
+        min_quote_out: u64,
+    ) -> Result<()> {
+        require!(lp_amount > 0, ErrorCode::InvalidAmount);
+        require!(ctx.accounts.kyc_record.is_verified, ErrorCode::KycNotVerified);
+
+        let pool = &ctx.accounts.pool;
+        let (token_reserve, quote_reserve) = pool.reserves(
+            ctx.accounts.token_vault.amount,
+            ctx.accounts.quote_vault.amount,
+        )?;
+        let lp_supply = ctx.accounts.lp_mint.supply;
+        require!(lp_supply > 0, ErrorCode::InsufficientLiquidity);
+
+        let token_out = (token_reserve as u128)
+            .checked_mul(lp_amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(lp_supply as u128)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        let quote_out = (quote_reserve as u128)
+            .checked_mul(lp_amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(lp_supply as u128)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+
+        require!(token_out >= min_token_out, ErrorCode::SlippageExceeded);
+        require!(quote_out >= min_quote_out, ErrorCode::SlippageExceeded);
+
+        // Settle pool income over the current LP supply before it changes
+        let income_per_token = ctx.accounts.property.income_per_token;
+        ctx.accounts.pool_record.settle_income(income_per_token)?;
+        ctx.accounts.pool.accrue_lp_income(&ctx.accounts.pool_record, lp_supply)?;
+        let lp_balance = ctx.accounts.provider_lp_account.amount;
+        ctx.accounts
+            .lp_position
+            .settle_income(ctx.accounts.pool.income_per_lp_share, lp_balance)?;
+
+        // Burn the provider's LP tokens, refreezing whatever remains
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        let pool_seeds: &[&[u8]] = &[b"pool", token_mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+        let signer_seeds = &[pool_seeds];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        if ctx.accounts.provider_lp_account.is_frozen() {
+            let cpi_accounts = ThawAccount {
+                account: ctx.accounts.provider_lp_account.to_account_info(),
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds))?;
+        }
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            from: ctx.accounts.provider_lp_account.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        token::burn(CpiContext::new(cpi_program.clone(), cpi_accounts), lp_amount)?;
+        if lp_balance > lp_amount {
+            let cpi_accounts = FreezeAccount {
+                account: ctx.accounts.provider_lp_account.to_account_info(),
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            token::freeze_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds))?;
+        }
+
+        // Pay out both sides from the pool vaults
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.token_vault.to_account_info(),
+            to: ctx.accounts.provider_token_account.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds), token_out)?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.quote_vault.to_account_info(),
+            to: ctx.accounts.provider_quote_account.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), quote_out)?;
+
+        let property_key = ctx.accounts.property.key();
+        let pool_record = &mut ctx.accounts.pool_record;
+        pool_record.tokens_owned = pool_record
+            .tokens_owned
+            .checked_sub(token_out)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let investor_record = &mut ctx.accounts.investor_record;
+        investor_record.investor = ctx.accounts.provider.key();
+        investor_record.property = property_key;
+        investor_record.settle_income(income_per_token)?;
+        investor_record.tokens_owned = investor_record
+            .tokens_owned
+            .checked_add(token_out)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LiquidityRemoved {
+            pool: ctx.accounts.pool.key(),
+            provider: ctx.accounts.provider.key(),
+            token_amount: token_out,
+            quote_amount: quote_out,
+            lp_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Swap against a pool with a minimum output for slippage protection
+    pub fn swap(
+        ctx: Context<Swap>,
+        direction: SwapDirection,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(amount_in > 0, ErrorCode::InvalidAmount);
+        require!(ctx.accounts.kyc_record.is_verified, ErrorCode::KycNotVerified);
+
+        let pool = &ctx.accounts.pool;
+        let (token_reserve, quote_reserve) = pool.reserves(
+            ctx.accounts.token_vault.amount,
+            ctx.accounts.quote_vault.amount,
+        )?;
+        let (reserve_in, reserve_out) = match direction {
+            SwapDirection::QuoteToToken => (quote_reserve, token_reserve),
+            SwapDirection::TokenToQuote => (token_reserve, quote_reserve),
+        };
+        require!(reserve_in > 0 && reserve_out > 0, ErrorCode::InsufficientLiquidity);
+
+        if direction == SwapDirection::TokenToQuote {
+            ctx.accounts.investor_record.require_unlocked(amount_in, Clock::get()?.unix_timestamp)?;
+        }
+
+        // The LP share of the fee stays in the reserves; the platform share is set aside
+        let fee = amount_in
+            .checked_mul(pool.fee_bps)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let platform_fee = fee
+            .checked_mul(pool.platform_fee_share_bps)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let amount_in_after_fee = amount_in - fee;
+
+        let amount_out = (reserve_out as u128)
+            .checked_mul(amount_in_after_fee as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(
+                (reserve_in as u128)
+                    .checked_add(amount_in_after_fee as u128)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+
+        require!(amount_out > 0, ErrorCode::InvalidAmount);
+        require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+
+        let (user_source, vault_destination, vault_source, user_destination) = match direction {
+            SwapDirection::QuoteToToken => (
+                ctx.accounts.user_quote_account.to_account_info(),
+                ctx.accounts.quote_vault.to_account_info(),
+                ctx.accounts.token_vault.to_account_info(),
+                ctx.accounts.user_token_account.to_account_info(),
+            ),
+            SwapDirection::TokenToQuote => (
+                ctx.accounts.user_token_account.to_account_info(),
+                ctx.accounts.token_vault.to_account_info(),
+                ctx.accounts.quote_vault.to_account_info(),
+                ctx.accounts.user_quote_account.to_account_info(),
+            ),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: user_source,
+            to: vault_destination,
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(CpiContext::new(cpi_program.clone(), cpi_accounts), amount_in)?;
+
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        let pool_seeds: &[&[u8]] = &[b"pool", token_mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+        let signer_seeds = &[pool_seeds];
+        let cpi_accounts = Transfer {
+            from: vault_source,
+            to: user_destination,
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount_out)?;
+
+        let pool = &mut ctx.accounts.pool;
+        let property_key = ctx.accounts.property.key();
+        let income_per_token = ctx.accounts.property.income_per_token;
+        let pool_record = &mut ctx.accounts.pool_record;
+        pool_record.settle_income(income_per_token)?;
+        let investor_record = &mut ctx.accounts.investor_record;
+        investor_record.investor = ctx.accounts.user.key();
+        investor_record.property = property_key;
+        investor_record.settle_income(income_per_token)?;
+        match direction {
+            SwapDirection::QuoteToToken => {
+                pool.platform_fees_quote = pool
+                    .platform_fees_quote
+                    .checked_add(platform_fee)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                pool_record.tokens_owned = pool_record
+                    .tokens_owned
+                    .checked_sub(amount_out)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                investor_record.tokens_owned = investor_record
+                    .tokens_owned
+                    .checked_add(amount_out)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            SwapDirection::TokenToQuote => {
+                pool.platform_fees_token = pool
+                    .platform_fees_token
+                    .checked_add(platform_fee)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                investor_record.tokens_owned = investor_record
+                    .tokens_owned
+                    .checked_sub(amount_in)
+                    .ok_or(ErrorCode::InsufficientTokens)?;
+                pool_record.tokens_owned = pool_record
+                    .tokens_owned
+                    .checked_add(amount_in)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+
+        emit!(TokensSwapped {
+            property_id: ctx.accounts.property.property_id.clone(),
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            direction,
+            amount_in,
+            amount_out,
+            fee,
+            platform_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw the platform's accrued share of pool swap fees
+    pub fn collect_pool_fees(ctx: Context<CollectPoolFees>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let token_amount = ctx.accounts.pool.platform_fees_token;
+        let quote_amount = ctx.accounts.pool.platform_fees_quote;
+
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        let pool_seeds: &[&[u8]] = &[b"pool", token_mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+        let signer_seeds = &[pool_seeds];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        if token_amount > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.destination_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds), token_amount)?;
+        }
+        if quote_amount > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.quote_vault.to_account_info(),
+                to: ctx.accounts.destination_quote_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), quote_amount)?;
+        }
+
+        // Collected fee tokens move from the pool's income record to the recipient's
+        let income_per_token = ctx.accounts.property.income_per_token;
+        let pool_record = &mut ctx.accounts.pool_record;
+        pool_record.settle_income(income_per_token)?;
+        pool_record.tokens_owned = pool_record
+            .tokens_owned
+            .checked_sub(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let destination_record = &mut ctx.accounts.destination_investor_record;
+        destination_record.investor = ctx.accounts.destination_token_account.owner;
+        destination_record.property = ctx.accounts.property.key();
+        destination_record.settle_income(income_per_token)?;
+        destination_record.tokens_owned = destination_record
+            .tokens_owned
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.platform_fees_token = 0;
+        pool.platform_fees_quote = 0;
+
+        emit!(PoolFeesCollected {
+            pool: pool.key(),
+            token_amount,
+            quote_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Claim a liquidity provider's share of the rental income earned by a pool's tokens
+    pub fn claim_lp_income(ctx: Context<ClaimLpIncome>) -> Result<()> {
+        let property = &ctx.accounts.property;
+        let pool_record = &mut ctx.accounts.pool_record;
+        pool_record.settle_income(property.income_per_token)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.accrue_lp_income(pool_record, ctx.accounts.lp_mint.supply)?;
+        let lp_position = &mut ctx.accounts.lp_position;
+        lp_position.settle_income(pool.income_per_lp_share, ctx.accounts.provider_lp_account.amount)?;
+        let claimable_amount = lp_position.pending_income;
+
+        require!(claimable_amount > 0, ErrorCode::NothingToClaim);
+        lp_position.pending_income = 0;
+
+        // LP income is paid out of the income held on the pool's record
+        pool_record.pending_income = pool_record
+            .pending_income
+            .checked_sub(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.income_indexed = pool
+            .income_indexed
+            .checked_sub(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool_record.total_claimed = pool_record
+            .total_claimed
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        match property.income_mint {
+            Some(_) => {
+                let income_token_vault = ctx
+                    .accounts
+                    .income_token_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let provider_income_account = ctx
+                    .accounts
+                    .provider_income_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                pay_income_tokens(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &income_token_vault.to_account_info(),
+                    &provider_income_account.to_account_info(),
+                    &property.key(),
+                    ctx.bumps.income_token_vault.ok_or(ErrorCode::MissingTokenAccount)?,
+                    claimable_amount,
+                )?;
+            }
+            None => {
+                **ctx.accounts.income_vault.to_account_info().try_borrow_mut_lamports()? -= claimable_amount;
+                **ctx.accounts.provider.to_account_info().try_borrow_mut_lamports()? += claimable_amount;
+            }
+        }
+        let income_vault = &mut ctx.accounts.income_vault;
+        income_vault.total_claimed = income_vault
+            .total_claimed
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        lp_position.total_claimed = lp_position
+            .total_claimed
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LpIncomeClaimed {
+            property_id: property.property_id.clone(),
+            pool: pool.key(),
+            provider: ctx.accounts.provider.key(),
+            amount: claimable_amount,
+            total_claimed: lp_position.total_claimed,
+        });
+
+        Ok(())
+    }
+
+    /// Initiate property sale (requires governance vote)
+    pub fn initiate_property_sale(
+        ctx: Context<InitiatePropertySale>,
+        asking_price: u64,
+        chainlink_valuation: u64,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+        
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        property.is_for_sale = true;
+        property.asking_price = asking_price;
+        property.market_valuation = chainlink_valuation;
+        property.sale_initiated_at = Clock::get()?.unix_timestamp;
+
+        emit!(PropertySaleInitiated {
+            property_id: property.property_id.clone(),
+            asking_price,
+            market_valuation: chainlink_valuation,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Execute property sale and distribute proceeds
+    pub fn execute_property_sale(
+        ctx: Context<ExecutePropertySale>,
+        sale_price: u64,
+        buyer_address: Pubkey,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+        let platform_state = &mut ctx.accounts.platform_state;
+        
+        require!(property.is_active, ErrorCode::PropertyNotActive);
+        require!(property.is_for_sale, ErrorCode::PropertyNotForSale);
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        // Calculate platform fee
+        let platform_fee = sale_price
+            .checked_mul(platform_state.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let net_proceeds = sale_price
+            .checked_sub(platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // A sold property no longer counts towards TVL
+        platform_state.adjust_value_locked(&property.property_type, property.property_valuation, 0)?;
+        property.is_active = false;
+        property.is_for_sale = false;
+        property.final_sale_price = sale_price;
+        property.sale_completed_at = Clock::get()?.unix_timestamp;
+
+        emit!(PropertySold {
+            property_id: property.property_id.clone(),
+            sale_price,
+            platform_fee,
+            net_proceeds,
+            buyer: buyer_address,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Distribute rental income and push each batch investor's share straight from the income
+    /// vault. The first call for a round funds it; later calls with `total_income == 0` resume
+    /// paying the remaining holders, skipping anyone already paid in this round.
+    pub fn batch_distribute_rental_income<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchDistributeRentalIncome<'info>>,
+        total_income: u64,
+        chainlink_round_id: u64,
+        investor_addresses: Vec<Pubkey>,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+        let platform_state = &ctx.accounts.platform_state;
+        
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(property.tokens_sold > 0, ErrorCode::NoTokensIssued);
+        require!(investor_addresses.len() <= 50, ErrorCode::TooManyInvestors); // Limit batch size
+        require!(
+            ctx.remaining_accounts.len() == investor_addresses.len() * 2, // 2 accounts per investor
+            ErrorCode::InvalidAccountsLength
+        );
+
+        let distribution = &mut ctx.accounts.distribution;
+        if distribution.property == Pubkey::default() {
+            // First call for this round: fund the income vault and open the round
+            require!(total_income > 0, ErrorCode::InvalidAmount);
+
+            // Calculate platform fee
+            let platform_fee = total_income
+                .checked_mul(platform_state.platform_fee)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(10000)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            let distributable_income = total_income
+                .checked_sub(platform_fee)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            match property.income_mint {
+                Some(income_mint) => {
+                    let distributor_income_account = ctx
+                        .accounts
+                        .distributor_income_account
+                        .as_ref()
+                        .ok_or(ErrorCode::MissingTokenAccount)?;
+                    let income_token_vault = ctx
+                        .accounts
+                        .income_token_vault
+                        .as_ref()
+                        .ok_or(ErrorCode::MissingTokenAccount)?;
+                    require!(
+                        distributor_income_account.mint == income_mint,
+                        ErrorCode::InvalidIncomeMint
+                    );
+                    fund_rental_income_tokens(
+                        &ctx.accounts.token_program,
+                        &ctx.accounts.authority,
+                        distributor_income_account,
+                        ctx.accounts.treasury_token_account.as_deref(),
+                        income_token_vault,
+                        &mut ctx.accounts.income_vault,
+                        platform_fee,
+                        distributable_income,
+                    )?;
+                }
+                None => {
+                    fund_rental_income(
+                        &ctx.accounts.authority,
+                        &mut ctx.accounts.treasury,
+                        &mut ctx.accounts.income_vault,
+                        platform_fee,
+                        distributable_income,
+                    )?;
+                }
+            }
+
+            property.accrue_income(distributable_income)?;
+            property.last_income_distribution = Clock::get()?.unix_timestamp;
+            property.distribution_count = property
+                .distribution_count
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            distribution.property = property.key();
+            distribution.round_id = chainlink_round_id;
+            distribution.sequence = property.distribution_count;
+            distribution.total_income = total_income;
+            distribution.distributable_income = distributable_income;
+            distribution.investors_paid = 0;
+            distribution.amount_paid = 0;
+            distribution.created_at = Clock::get()?.unix_timestamp;
+            distribution.bump = ctx.bumps.distribution;
+
+            emit!(RentalIncomeDistributed {
+                property_id: property.property_id.clone(),
+                total_income,
+                platform_fee,
+                distributable_income,
+                chainlink_round_id,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        } else {
+            // Resuming an open round: funding already happened
+            require!(total_income == 0, ErrorCode::DistributionAlreadyFunded);
+        }
+
+        // Process each investor in the batch using remaining_accounts
+        // Pattern: [investor_record, destination] per investor, where destination is the investor's
+        // wallet, or their income-mint token account when the property pays income in SPL tokens
+        let mut batch_paid = 0u64;
+        let (pool_key, _) = Pubkey::find_program_address(&[b"pool", property.token_mint.as_ref()], &crate::ID);
+        for (i, investor_address) in investor_addresses.iter().enumerate() {
+            let investor_record_info = &ctx.remaining_accounts[i * 2];
+            let destination_info = &ctx.remaining_accounts[i * 2 + 1];
+            
+            // Deserialize the investor record
+            require!(investor_record_info.owner == &crate::ID, ErrorCode::InvalidInvestorRecord);
+            let mut investor_record_data = investor_record_info.try_borrow_mut_data()?;
+            let mut investor_record = InvestorRecord::try_deserialize(&mut investor_record_data.as_ref())?;
+            
+            // Verify the investor record matches the provided address
+            require!(
+                investor_record.investor == *investor_address &&
+                investor_record.property == property.key(),
+                ErrorCode::InvalidInvestorRecord
+            );
+
+            // Already paid in this round (or a later one) by an earlier batch. Pooled tokens'
+            // income is left on the pool's record for liquidity providers to claim.
+            if investor_record.last_distribution_paid >= distribution.sequence || *investor_address == pool_key {
+                continue;
+            }
+
+            investor_record.settle_income(property.income_per_token)?;
+            let investor_share = investor_record.pending_income;
+
+            if investor_share > 0 {
+                match property.income_mint {
+                    Some(income_mint) => {
+                        let income_token_vault = ctx
+                            .accounts
+                            .income_token_vault
+                            .as_ref()
+                            .ok_or(ErrorCode::MissingTokenAccount)?;
+                        let investor_income_account = Account::<TokenAccount>::try_from(destination_info)?;
+                        require!(
+                            investor_income_account.owner == *investor_address &&
+                            investor_income_account.mint == income_mint,
+                            ErrorCode::InvalidIncomeMint
+                        );
+                        pay_income_tokens(
+                            &ctx.accounts.token_program.to_account_info(),
+                            &income_token_vault.to_account_info(),
+                            destination_info,
+                            &property.key(),
+                            ctx.bumps.income_token_vault.ok_or(ErrorCode::MissingTokenAccount)?,
+                            investor_share,
+                        )?;
+                    }
+                    None => {
+                        require!(destination_info.key() == *investor_address, ErrorCode::InvalidInvestorRecord);
+                        // Transfer SOL from the income vault to investor
+                        **ctx.accounts.income_vault.to_account_info().try_borrow_mut_lamports()? -= investor_share;
+                        **destination_info.try_borrow_mut_lamports()? += investor_share;
+                    }
+                }
+
+                investor_record.pending_income = 0;
+                investor_record.total_claimed += investor_share;
+                investor_record.last_claim_time = Clock::get()?.unix_timestamp;
+                batch_paid = batch_paid
+                    .checked_add(investor_share)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            investor_record.last_distribution_paid = distribution.sequence;
+
+            // Serialize the updated investor record back
+            let mut updated_data = Vec::new();
+            investor_record.try_serialize(&mut updated_data)?;
+            investor_record_data[..updated_data.len()].copy_from_slice(&updated_data);
+
+            distribution.investors_paid = distribution
+                .investors_paid
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            emit!(BatchRentalIncomeDistributed {
+                property_id: property.property_id.clone(),
+                investor: *investor_address,
+                amount: investor_share,
+                batch_id: chainlink_round_id,
+            });
+        }
+
+        distribution.amount_paid = distribution
+            .amount_paid
+            .checked_add(batch_paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let income_vault = &mut ctx.accounts.income_vault;
+        income_vault.total_claimed = income_vault
+            .total_claimed
+            .checked_add(batch_paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Batch transfer tokens to multiple recipients for gas efficiency
+    pub fn batch_transfer_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransferTokens<'info>>,
+        transfers: Vec<TokenTransfer>,
+    ) -> Result<()> {
+        require!(transfers.len() <= 20, ErrorCode::TooManyTransfers); // Limit batch size
+        require!(
+            ctx.remaining_accounts.len() == transfers.len() * 2, // 2 accounts per recipient
+            ErrorCode::InvalidAccountsLength
+        );
+        
+        let property = &ctx.accounts.property;
+        let property_key = property.key();
+        
+        // Calculate total tokens being transferred
+        let mut total_amount = 0u64;
+        for transfer in &transfers {
+            require!(transfer.amount > 0, ErrorCode::InvalidAmount);
+            total_amount = total_amount
+                .checked_add(transfer.amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        
+        let from_record = &mut ctx.accounts.from_investor_record;
+        from_record.require_unlocked(total_amount, Clock::get()?.unix_timestamp)?;
+
+        // Update sender's record
+        from_record.settle_income(property.income_per_token)?;
+        from_record.tokens_owned = from_record
+            .tokens_owned
+            .checked_sub(total_amount)
+            .ok_or(ErrorCode::InsufficientTokens)?;
+
+        // Process each transfer in the batch using remaining_accounts
+        // Pattern: [to_token_account, to_investor_record] for each recipient
+        for (i, transfer) in transfers.iter().enumerate() {
+            let to_token_account_info = &ctx.remaining_accounts[i * 2];
+            let to_record_info = &ctx.remaining_accounts[i * 2 + 1];
+
+            let to_token_account = Account::<TokenAccount>::try_from(to_token_account_info)?;
+            require!(
+                to_token_account.owner == transfer.recipient &&
+                to_token_account.mint == property.token_mint,
+                ErrorCode::InvalidTokenAccount
+            );
+
+            // Checkpoint and credit the recipient's investor record
+            require!(to_record_info.owner == &crate::ID, ErrorCode::InvalidInvestorRecord);
+            let mut to_record_data = to_record_info.try_borrow_mut_data()?;
+            let mut to_record = InvestorRecord::try_deserialize(&mut to_record_data.as_ref())?;
+            require!(
+                to_record.investor == transfer.recipient && to_record.property == property_key,
+                ErrorCode::InvalidInvestorRecord
+            );
+            to_record.settle_income(property.income_per_token)?;
+            to_record.tokens_owned = to_record
+                .tokens_owned
+                .checked_add(transfer.amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let mut updated_data = Vec::new();
+            to_record.try_serialize(&mut updated_data)?;
+            to_record_data[..updated_data.len()].copy_from_slice(&updated_data);
+            drop(to_record_data);
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.from_token_account.to_account_info(),
+                to: to_token_account_info.clone(),
+                authority: ctx.accounts.from.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(CpiContext::new(cpi_program, cpi_accounts), transfer.amount)?;
+
+            emit!(BatchTokensTransferred {
+                property_id: property.property_id.clone(),
+                from: ctx.accounts.from.key(),
+                to: transfer.recipient,
+                amount: transfer.amount,
+                batch_index: i as u8,
+            });
+        }
+
+        emit!(BatchTransferCompleted {
+            property_id: property.property_id.clone(),
+            from: ctx.accounts.from.key(),
+            total_amount,
+            transfer_count: transfers.len() as u8,
+        });
+
+        Ok(())
+    }
+
+    /// Batch update KYC status for multiple users for gas efficiency
+    pub fn batch_update_kyc_status(
+        ctx: Context<BatchUpdateKycStatus>,
+        kyc_updates: Vec<KycUpdate>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(kyc_updates.len() <= 30, ErrorCode::TooManyKycUpdates); // Limit batch size
+        require!(
+            ctx.remaining_accounts.len() == kyc_updates.len(),
+            ErrorCode::InvalidAccountsLength
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Process each KYC update in the batch using remaining_accounts
+        for (i, kyc_update) in kyc_updates.iter().enumerate() {
+            let kyc_record_info = &ctx.remaining_accounts[i];
+            
+            // Deserialize and update the KYC record
+            let mut kyc_record_data = kyc_record_info.try_borrow_mut_data()?;
+            let mut kyc_record = KycRecord::try_deserialize(&mut kyc_record_data.as_ref())?;
+            
+            // Verify the KYC record matches the provided user
+            require!(
+                kyc_record.user == kyc_update.user,
+                ErrorCode::InvalidKycRecord
+            );
+
+            kyc_record.is_verified = kyc_update.is_verified;
+            kyc_record.updated_at = current_time;
+            kyc_record.verification_provider = "Chainlink".to_string();
+            kyc_record.round_id = kyc_update.chainlink_round_id;
+
+            // Serialize the updated record back
+            let mut updated_data = Vec::new();
+            kyc_record.try_serialize(&mut updated_data)?;
+            kyc_record_data[..updated_data.len()].copy_from_slice(&updated_data);
+
+            emit!(BatchKycStatusUpdated {
+                user: kyc_update.user,
+                is_verified: kyc_update.is_verified,
+                updated_at: current_time,
+                batch_index: i as u8,
+            });
+        }
+
+        emit!(BatchKycUpdateCompleted {
+            total_updates: kyc_updates.len() as u8,
+            updated_at: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Batch claim rental income for multiple properties for gas efficiency
+    pub fn batch_claim_rental_income<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchClaimRentalIncome<'info>>,
+        property_keys: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(property_keys.len() <= 10, ErrorCode::TooManyProperties); // Limit batch size
+        require!(
+            ctx.remaining_accounts.len() >= property_keys.len() * 3, // At least 3 accounts per property
+            ErrorCode::InvalidAccountsLength
+        );
+        
+        let investor = &ctx.accounts.investor;
+        let mut total_claimed = 0u64;
+        let mut next_account = 0usize;
+
+        // Process each property claim in the batch using remaining_accounts
+        // Pattern: [property, investor_record, income_vault] for each property, followed by
+        // [income_token_vault, investor_income_account] when the property pays income in SPL tokens
+        for (i, property_key) in property_keys.iter().enumerate() {
+            let property_info = next_remaining_account(ctx.remaining_accounts, &mut next_account)?;
+            let investor_record_info = next_remaining_account(ctx.remaining_accounts, &mut next_account)?;
+            let income_vault_info = next_remaining_account(ctx.remaining_accounts, &mut next_account)?;
+            
+            // Verify the property matches
+            require!(property_info.key() == *property_key, ErrorCode::InvalidPropertyKey);
+            require!(property_info.owner == &crate::ID, ErrorCode::InvalidPropertyKey);
+            
+            // Deserialize property
+            let property_data = property_info.try_borrow_data()?;
+            let property = Property::try_deserialize(&mut property_data.as_ref())?;
+            let income_token_accounts = match property.income_mint {
+                Some(_) => Some((
+                    next_remaining_account(ctx.remaining_accounts, &mut next_account)?,
+                    next_remaining_account(ctx.remaining_accounts, &mut next_account)?,
+                )),
+                None => None,
+            };
+            
+            // Deserialize and update investor record
+            require!(investor_record_info.owner == &crate::ID, ErrorCode::InvalidInvestorRecord);
+            let mut investor_record_data = investor_record_info.try_borrow_mut_data()?;
+            let mut investor_record = InvestorRecord::try_deserialize(&mut investor_record_data.as_ref())?;
+            require!(
+                investor_record.investor == investor.key() && investor_record.property == *property_key,
+                ErrorCode::InvalidInvestorRecord
+            );
+            
+            investor_record.settle_income(property.income_per_token)?;
+            let claimable_amount = investor_record.pending_income;
+
+            if claimable_amount > 0 {
+                // Verify and update the property's income vault
+                require!(income_vault_info.owner == &crate::ID, ErrorCode::InvalidIncomeVault);
+                let mut income_vault_data = income_vault_info.try_borrow_mut_data()?;
+                let mut income_vault = IncomeVault::try_deserialize(&mut income_vault_data.as_ref())?;
+                require!(income_vault.property == *property_key, ErrorCode::InvalidIncomeVault);
+                income_vault.total_claimed = income_vault
+                    .total_claimed
+                    .checked_add(claimable_amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                let mut updated_vault = Vec::new();
+                income_vault.try_serialize(&mut updated_vault)?;
+                income_vault_data[..updated_vault.len()].copy_from_slice(&updated_vault);
+                drop(income_vault_data);
+
+                match income_token_accounts {
+                    Some((income_token_vault_info, investor_income_account_info)) => {
+                        let (income_token_vault, bump) = Pubkey::find_program_address(
+                            &[b"income_token_vault", property_key.as_ref()],
+                            &crate::ID,
+                        );
+                        require!(
+                            income_token_vault_info.key() == income_token_vault,
+                            ErrorCode::InvalidIncomeVault
+                        );
+                        pay_income_tokens(
+                            &ctx.accounts.token_program.to_account_info(),
+                            income_token_vault_info,
+                            investor_income_account_info,
+                            property_key,
+                            bump,
+                            claimable_amount,
+                        )?;
+                    }
+                    None => {
+                        // Transfer SOL from the income vault to investor
+                        **income_vault_info.try_borrow_mut_lamports()? -= claimable_amount;
+                        **investor.to_account_info().try_borrow_mut_lamports()? += claimable_amount;
+                    }
+                }
+
+                investor_record.pending_income = 0;
+                investor_record.total_claimed += claimable_amount;
+                investor_record.last_claim_time = Clock::get()?.unix_timestamp;
+                
+                // Serialize the updated investor record back
+                let mut updated_data = Vec::new();
+                investor_record.try_serialize(&mut updated_data)?;
+                investor_record_data[..updated_data.len()].copy_from_slice(&updated_data);
+                
+                total_claimed = total_claimed
+                    .checked_add(claimable_amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                emit!(BatchRentalIncomeClaimed {
+                    property_id: property.property_id.clone(),
+                    investor: investor.key(),
+                    amount: claimable_amount,
+                    batch_index: i as u8,
+                });
+            }
+        }
+        require!(
+            next_account == ctx.remaining_accounts.len(),
+            ErrorCode::InvalidAccountsLength
+        );
+
+        emit!(BatchClaimCompleted {
+            investor: investor.key(),
+            total_claimed,
+            properties_count: property_keys.len() as u8,
+        });
+
+        Ok(())
+    }
+
+    /// Create a governance proposal
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        proposal_type: ProposalType,
+        voting_period: i64,
+    ) -> Result<()> {
+        let property = &ctx.accounts.property;
+        let investor_record = &ctx.accounts.investor_record;
+        let platform_state = &ctx.accounts.platform_state;
+        
+        require!(
+            investor_record.tokens_owned >= platform_state.governance_threshold,
+            ErrorCode::InsufficientTokensForProposal
+        );
+        
+        require!(title.len() <= 50, ErrorCode::TitleTooLong);
+        require!(description.len() <= 200, ErrorCode::DescriptionTooLong);
+        require!(voting_period > 0, ErrorCode::InvalidVotingPeriod);
+
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        proposal.property = ctx.accounts.property.key();
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.title = title.clone();
+        proposal.description = description;
+        proposal.proposal_type = proposal_type.clone();
+        proposal.votes_for = 0;
+        proposal.votes_against = 0;
+        proposal.total_votes = 0;
+        proposal.created_at = current_time;
+        proposal.voting_ends_at = current_time + voting_period;
+        proposal.executed = false;
+        proposal.passed = false;
+
+        emit!(ProposalCreated {
+            property_id: property.property_id.clone(),
+            proposer: ctx.accounts.proposer.key(),
+            title,
+            proposal_type,
+            voting_ends_at: proposal.voting_ends_at,
+        });
+
+        Ok(())
+    }
+
+    /// Vote on a governance proposal
+    pub fn vote_on_proposal(
+        ctx: Context<VoteOnProposal>,
+        vote_for: bool,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let investor_record = &ctx.accounts.investor_record;
+        let vote_record = &mut ctx.accounts.vote_record;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time <= proposal.voting_ends_at, ErrorCode::VotingPeriodEnded);
+        require!(investor_record.tokens_owned > 0, ErrorCode::NoTokensOwned);
+        require!(!vote_record.has_voted, ErrorCode::AlreadyVoted);
+
+        let voting_power = investor_record.tokens_owned;
+        
+        if vote_for {
+            proposal.votes_for += voting_power;
+        } else {
+            proposal.votes_against += voting_power;
+        }
+        
+        proposal.total_votes += voting_power;
+        
+        vote_record.voter = ctx.accounts.voter.key();
+        vote_record.proposal = ctx.accounts.proposal.key();
+        vote_record.vote_for = vote_for;
+        vote_record.voting_power = voting_power;
+        vote_record.has_voted = true;
+        vote_record.voted_at = current_time;
+
+        emit!(VoteCast {
+            proposal: ctx.accounts.proposal.key(),
+            voter: ctx.accounts.voter.key(),
+            vote_for,
+            voting_power,
+        });
+
+        Ok(())
+    }
+
+    /// Execute a passed proposal
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let property = &ctx.accounts.property;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time > proposal.voting_ends_at, ErrorCode::VotingStillActive);
+        require!(!proposal.executed, ErrorCode::ProposalAlreadyExecuted);
+        
+        // Check if proposal passed
+        let passed = proposal.votes_for > proposal.votes_against && 
+                    proposal.total_votes > property.tokens_sold / 2;
+        
+        proposal.passed = passed;
+        proposal.executed = true;
+
+        emit!(ProposalExecuted {
+            proposal: proposal.key(),
+            passed,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+        });
+
+        Ok(())
+    }
+
+    /// Transfer tokens between users
+    pub fn transfer_tokens(
+        ctx: Context<TransferTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let property = &ctx.accounts.property;
+        let from_record = &mut ctx.accounts.from_investor_record;
+        let to_record = &mut ctx.accounts.to_investor_record;
+        
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        from_record.require_unlocked(amount, Clock::get()?.unix_timestamp)?;
+
+        // Transfer SPL tokens
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from_token_account.to_account_info(),
+            to: ctx.accounts.to_token_account.to_account_info(),
+            authority: ctx.accounts.from.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        token::transfer(cpi_ctx, amount)?;
+
+        // Update investor records
+        from_record.settle_income(property.income_per_token)?;
+        to_record.settle_income(property.income_per_token)?;
+        from_record.tokens_owned = from_record
+            .tokens_owned
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientTokens)?;
+        to_record.tokens_owned = to_record
+            .tokens_owned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(TokensTransferred {
+            property_id: property.property_id.clone(),
+            from: ctx.accounts.from.key(),
+            to: ctx.accounts.to.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Update KYC status
+    pub fn update_kyc_status(
+        ctx: Context<UpdateKycStatus>,
+        user: Pubkey,
+        is_verified: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let kyc_record = &mut ctx.accounts.kyc_record;
+        kyc_record.user = user;
+        kyc_record.is_verified = is_verified;
+        kyc_record.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(KycStatusUpdated {
+            user,
+            is_verified,
+            updated_at: kyc_record.updated_at,
+        });
+
+        Ok(())
+    }
+
+    /// Configure the oracle program that owns every price feed and the confidence limit
+    pub fn configure_oracle(
+        ctx: Context<ConfigureOracle>,
+        oracle_program: Pubkey,
+        max_confidence_bps: u64, // Max confidence interval relative to price, in basis points
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(max_confidence_bps <= 10000, ErrorCode::InvalidFee);
+
+        let platform_state = &mut ctx.accounts.platform_state;
+        platform_state.oracle_program = oracle_program;
+        platform_state.max_confidence_bps = max_confidence_bps;
+
+        emit!(OracleConfigured {
+            oracle_program,
+            max_confidence_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Register the oracle feeds aggregated for a price series. The subject is the platform
+    /// state for SOL/USD or a property for its valuation.
+    pub fn configure_price_sources(
+        ctx: Context<ConfigurePriceSources>,
+        sources: Vec<Pubkey>,
+        min_sources: u8,
+        max_deviation_bps: u64, // Max change from the previous value, in basis points
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.subject.key() == ctx.accounts.platform_state.key() ||
+            ctx.accounts.subject.owner == &crate::ID,
+            ErrorCode::InvalidOracleConfig
+        );
+        require!(
+            !sources.is_empty() && sources.len() <= OracleConfig::MAX_SOURCES,
+            ErrorCode::InvalidOracleConfig
+        );
+        require!(
+            min_sources > 0 && min_sources as usize <= sources.len(),
+            ErrorCode::InvalidOracleConfig
+        );
+        for (i, source) in sources.iter().enumerate() {
+            require!(!sources[..i].contains(source), ErrorCode::InvalidOracleConfig);
+        }
+
+        let oracle_config = &mut ctx.accounts.oracle_config;
+        oracle_config.subject = ctx.accounts.subject.key();
+        oracle_config.sources = sources.clone();
+        oracle_config.min_sources = min_sources;
+        oracle_config.max_deviation_bps = max_deviation_bps;
+        oracle_config.bump = ctx.bumps.oracle_config;
+
+        emit!(PriceSourcesConfigured {
+            subject: oracle_config.subject,
+            sources,
+            min_sources,
+            max_deviation_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Create the observation ring buffer for a price series. The subject is the platform
+    /// state for SOL/USD or a property for its valuation.
+    pub fn initialize_price_history(ctx: Context<InitializePriceHistory>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.subject.key() == ctx.accounts.platform_state.key() ||
+            ctx.accounts.subject.owner == &crate::ID,
+            ErrorCode::InvalidOracleConfig
+        );
+
+        let price_history = &mut ctx.accounts.price_history;
+        price_history.subject = ctx.accounts.subject.key();
+        price_history.head = 0;
+        price_history.observations = Vec::new();
+        price_history.bump = ctx.bumps.price_history;
+
+        Ok(())
+    }
+
+    /// Update the SOL/USD TWAP window used to price USD-denominated trades
+    pub fn update_twap_window(
+        ctx: Context<UpdateTwapWindow>,
+        twap_window: i64, // In seconds; 0 = use the latest spot price
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(twap_window >= 0, ErrorCode::InvalidTwapWindow);
+
+        ctx.accounts.platform_state.twap_window = twap_window;
+
+        Ok(())
+    }
+
+    /// Compute the time-weighted average of a price series over the trailing window
+    pub fn get_twap(ctx: Context<GetTwap>, window: i64) -> Result<u64> {
+        require!(window > 0, ErrorCode::InvalidTwapWindow);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let twap = ctx.accounts.price_history.twap(current_time, window)?;
+
+        emit!(TwapComputed {
+            subject: ctx.accounts.price_history.subject,
+            window,
+            twap,
+            timestamp: current_time,
+        });
+
+        Ok(twap)
+    }
+
+    /// Update SOL/USD price from the median of the configured oracle feeds.
+    /// `force` lets the platform authority bypass the deviation guard.
+    pub fn update_sol_price(
+        ctx: Context<UpdateSolPrice>,
+        force: bool,
+    ) -> Result<()> {
+        if force {
+            require!(
+                ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+                ErrorCode::Unauthorized
+            );
+        }
+
+        // Price in USD with 8 decimals (e.g., 10000000000 = $100.00)
+        // remaining_accounts: [feed_1, feed_2, ...]
+        let oracle_config = &ctx.accounts.oracle_config;
+        let reading = oracle_config.aggregate(
+            ctx.remaining_accounts,
+            &ctx.accounts.platform_state,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let platform_state = &mut ctx.accounts.platform_state;
+        let old_price = platform_state.sol_usd_price;
+        if !oracle_config.within_deviation(old_price, reading.value)? {
+            require!(force, ErrorCode::PriceDeviationTooLarge);
+            emit!(OracleDeviationOverridden {
+                subject: platform_state.key(),
+                old_value: old_price,
+                new_value: reading.value,
+                authority: ctx.accounts.authority.key(),
+            });
+        }
+        platform_state.sol_usd_price = reading.value;
+        platform_state.last_price_update = reading.publish_time;
+
+        if let Some(price_history) = ctx.accounts.price_history.as_mut() {
+            price_history.record(Clock::get()?.unix_timestamp, reading.value, reading.round_id);
+        }
+        
+        emit!(SolPriceUpdated {
+            new_price: reading.value,
+            chainlink_round_id: reading.round_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Update how old the stored SOL/USD price may be before USD-priced trades are rejected
+    pub fn update_price_staleness_window(
+        ctx: Context<UpdatePriceStalenessWindow>,
+        price_staleness_window: i64, // In seconds
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(price_staleness_window > 0, ErrorCode::InvalidStalenessWindow);
+
+        ctx.accounts.platform_state.price_staleness_window = price_staleness_window;
+
+        Ok(())
+    }
+
+    /// Pay a property's rental income in an SPL mint (e.g. USDC) instead of SOL, creating the
+    /// income token vault. Only allowed before any income has been distributed.
+    pub fn set_income_mint(ctx: Context<SetIncomeMint>) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(property.income_per_token == 0, ErrorCode::InvalidIncomeMint);
+
+        property.income_mint = Some(ctx.accounts.income_mint.key());
+
+        emit!(IncomeMintSet {
+            property_id: property.property_id.clone(),
+            income_mint: ctx.accounts.income_mint.key(),
+            income_token_vault: ctx.accounts.income_token_vault.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Create the vault that holds a property's distributed rental income until it is claimed
+    pub fn initialize_income_vault(ctx: Context<InitializeIncomeVault>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.property.owner ||
+            ctx.accounts.authority.key() == ctx.accounts.platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let income_vault = &mut ctx.accounts.income_vault;
+        income_vault.property = ctx.accounts.property.key();
+        income_vault.total_deposited = 0;
+        income_vault.total_claimed = 0;
+        income_vault.bump = ctx.bumps.income_vault;
+
+        Ok(())
+    }
+
+    /// Distribute rental income to token holders (individual)
+    pub fn distribute_rental_income(
+        ctx: Context<DistributeRentalIncome>,
+        total_income: u64,
+        chainlink_round_id: u64,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+        let platform_state = &ctx.accounts.platform_state;
+        
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(total_income > 0, ErrorCode::InvalidAmount);
+        require!(property.tokens_sold > 0, ErrorCode::NoTokensIssued);
+
+        // Calculate platform fee
+        let platform_fee = total_income
+            .checked_mul(platform_state.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let distributable_income = total_income
+            .checked_sub(platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        match property.income_mint {
+            Some(income_mint) => {
+                let distributor_income_account = ctx
+                    .accounts
+                    .distributor_income_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let income_token_vault = ctx
+                    .accounts
+                    .income_token_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                require!(
+                    distributor_income_account.mint == income_mint,
+                    ErrorCode::InvalidIncomeMint
+                );
+                fund_rental_income_tokens(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.authority,
+                    distributor_income_account,
+                    ctx.accounts.treasury_token_account.as_deref(),
+                    income_token_vault,
+                    &mut ctx.accounts.income_vault,
+                    platform_fee,
+                    distributable_income,
+                )?;
+            }
+            None => {
+                fund_rental_income(
+                    &ctx.accounts.authority,
+                    &mut ctx.accounts.treasury,
+                    &mut ctx.accounts.income_vault,
+                    platform_fee,
+                    distributable_income,
+                )?;
+            }
+        }
+
+        property.accrue_income(distributable_income)?;
+        property.last_income_distribution = Clock::get()?.unix_timestamp;
+
+        emit!(RentalIncomeDistributed {
+            property_id: property.property_id.clone(),
+            total_income,
+            platform_fee,
+            distributable_income,
+            chainlink_round_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claim rental income for an investor (individual)
+    pub fn claim_rental_income(ctx: Context<ClaimRentalIncome>) -> Result<()> {
+        let property = &ctx.accounts.property;
+        let investor_record = &mut ctx.accounts.investor_record;
+        
+        investor_record.settle_income(property.income_per_token)?;
+        let claimable_amount = investor_record.pending_income;
+
+        require!(claimable_amount > 0, ErrorCode::NothingToClaim);
+        investor_record.pending_income = 0;
+
+        match property.income_mint {
+            Some(_) => {
+                let income_token_vault = ctx
+                    .accounts
+                    .income_token_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let investor_income_account = ctx
+                    .accounts
+                    .investor_income_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                pay_income_tokens(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &income_token_vault.to_account_info(),
+                    &investor_income_account.to_account_info(),
+                    &property.key(),
+                    ctx.bumps.income_token_vault.ok_or(ErrorCode::MissingTokenAccount)?,
+                    claimable_amount,
+                )?;
+            }
+            None => {
+                // Transfer SOL from the income vault to investor
+                **ctx.accounts.income_vault.to_account_info().try_borrow_mut_lamports()? -= claimable_amount;
+                **ctx.accounts.investor.to_account_info().try_borrow_mut_lamports()? += claimable_amount;
+            }
+        }
+        let income_vault = &mut ctx.accounts.income_vault;
+        income_vault.total_claimed = income_vault
+            .total_claimed
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        investor_record.total_claimed += claimable_amount;
+        investor_record.last_claim_time = Clock::get()?.unix_timestamp;
+
+        emit!(RentalIncomeClaimed {
+            property_id: property.property_id.clone(),
+            investor: ctx.accounts.investor.key(),
+            amount: claimable_amount,
+            total_claimed: investor_record.total_claimed,
+        });
+
+        Ok(())
+    }
+
+    /// Post a period's rental income as a Merkle root of (index, investor, amount) leaves computed
+    /// off-chain from a holder snapshot, and fund the income vault with it
+    pub fn create_merkle_distribution(
+        ctx: Context<CreateMerkleDistribution>,
+        distribution_id: u64,
+        merkle_root: [u8; 32],
+        total_income: u64,
+        num_leaves: u32,
+    ) -> Result<()> {
+        let property = &mut ctx.accounts.property;
+        let platform_state = &ctx.accounts.platform_state;
+
+        require!(
+            ctx.accounts.authority.key() == property.owner ||
+            ctx.accounts.authority.key() == platform_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(total_income > 0, ErrorCode::InvalidAmount);
+        require!(
+            num_leaves > 0 && num_leaves <= MerkleDistribution::MAX_LEAVES,
+            ErrorCode::InvalidAmount
+        );
+
+        // Calculate platform fee
+        let platform_fee = total_income
+            .checked_mul(platform_state.platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let distributable_income = total_income
+            .checked_sub(platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        match property.income_mint {
+            Some(income_mint) => {
+                let distributor_income_account = ctx
+                    .accounts
+                    .distributor_income_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let income_token_vault = ctx
+                    .accounts
+                    .income_token_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                require!(
+                    distributor_income_account.mint == income_mint,
+                    ErrorCode::InvalidIncomeMint
+                );
+                fund_rental_income_tokens(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.authority,
+                    distributor_income_account,
+                    ctx.accounts.treasury_token_account.as_deref(),
+                    income_token_vault,
+                    &mut ctx.accounts.income_vault,
+                    platform_fee,
+                    distributable_income,
+                )?;
+            }
+            None => {
+                fund_rental_income(
+                    &ctx.accounts.authority,
+                    &mut ctx.accounts.treasury,
+                    &mut ctx.accounts.income_vault,
+                    platform_fee,
+                    distributable_income,
+                )?;
+            }
+        }
+
+        // Snapshot payouts bypass the per-token index, so track the income here
+        property.total_rental_income = property
+            .total_rental_income
+            .checked_add(distributable_income)
+            .ok_or(ErrorCode::MathOverflow)?;
+        property.last_income_distribution = Clock::get()?.unix_timestamp;
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.property = property.key();
+        distribution.distribution_id = distribution_id;
+        distribution.merkle_root = merkle_root;
+        distribution.total_amount = distributable_income;
+        distribution.total_claimed = 0;
+        distribution.num_leaves = num_leaves;
+        distribution.income_mint = property.income_mint;
+        distribution.created_at = Clock::get()?.unix_timestamp;
+        distribution.bump = ctx.bumps.distribution;
+
+        let claimed_bitmap = &mut ctx.accounts.claimed_bitmap;
+        claimed_bitmap.distribution = distribution.key();
+        claimed_bitmap.bits = vec![0; (num_leaves as usize).div_ceil(8)];
+
+        emit!(MerkleDistributionCreated {
+            property_id: property.property_id.clone(),
+            distribution_id,
+            merkle_root,
+            total_income,
+            platform_fee,
+            distributable_income,
+            num_leaves,
+        });
+
+        Ok(())
+    }
+
+    /// Claim an investor's share of a Merkle distribution with a proof of their leaf
+    pub fn claim_merkle_income(
+        ctx: Context<ClaimMerkleIncome>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let property = &ctx.accounts.property;
+        let distribution = &mut ctx.accounts.distribution;
+        let claimed_bitmap = &mut ctx.accounts.claimed_bitmap;
+        let investor = ctx.accounts.investor.key();
+
+        require!(index < distribution.num_leaves, ErrorCode::InvalidMerkleProof);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(!claimed_bitmap.is_claimed(index), ErrorCode::AlreadyClaimed);
+
+        let leaf = MerkleDistribution::leaf(index, &investor, amount);
+        require!(
+            verify_merkle_proof(&proof, distribution.merkle_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        // A bad snapshot must not draw on income owed to other distributions
+        let total_claimed = distribution
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(total_claimed <= distribution.total_amount, ErrorCode::InsufficientFunds);
+        distribution.total_claimed = total_claimed;
+        claimed_bitmap.set_claimed(index);
+
+        match distribution.income_mint {
+            Some(income_mint) => {
+                let income_token_vault = ctx
+                    .accounts
+                    .income_token_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                let investor_income_account = ctx
+                    .accounts
+                    .investor_income_account
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                require!(
+                    investor_income_account.owner == investor &&
+                    investor_income_account.mint == income_mint,
+                    ErrorCode::InvalidIncomeMint
+                );
+                pay_income_tokens(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &income_token_vault.to_account_info(),
+                    &investor_income_account.to_account_info(),
+                    &property.key(),
+                    ctx.bumps.income_token_vault.ok_or(ErrorCode::MissingTokenAccount)?,
+                    amount,
+                )?;
+            }
+            None => {
+                // Transfer SOL from the income vault to investor
+                **ctx.accounts.income_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+                **ctx.accounts.investor.to_account_info().try_borrow_mut_lamports()? += amount;
+            }
+        }
+        let income_vault = &mut ctx.accounts.income_vault;
+        income_vault.total_claimed = income_vault
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(MerkleIncomeClaimed {
+            property_id: property.property_id.clone(),
+            distribution_id: distribution.distribution_id,
+            investor,
+            index,
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+/// Release all tokens held in a listing or auction escrow to `destination_token_account`
+/// and close the escrow, sending its rent to `rent_destination`. Returns the amount released.
+fn release_escrow<'info>(
+    token_program: &Program<'info, Token>,
+    escrow_token_account: &Account<'info, TokenAccount>,
+    destination_token_account: &Account<'info, TokenAccount>,
+    rent_destination: &AccountInfo<'info>,
+    escrow_owner_key: Pubkey,
+    escrow_bump: u8,
+) -> Result<u64> {
+    let escrow_seeds: &[&[u8]] = &[b"escrow", escrow_owner_key.as_ref(), &[escrow_bump]];
+    let signer_seeds = &[escrow_seeds];
+    let amount = escrow_token_account.amount;
+
+    if amount > 0 {
+        let cpi_accounts = Transfer {
+            from: escrow_token_account.to_account_info(),
+            to: destination_token_account.to_account_info(),
+            authority: escrow_token_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, amount)?;
+    }
+
+    // Close the escrow token account, reclaiming its rent
+    let cpi_accounts = CloseAccount {
+        account: escrow_token_account.to_account_info(),
+        destination: rent_destination.clone(),
+        authority: escrow_token_account.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::close_account(cpi_ctx)?;
+
+    Ok(amount)
+}
+
+/// Split of a secondary trade's SOL payment between the platform, property owner and seller
+struct TradeProceeds {
+    platform_fee: u64,
+    royalty: u64,
+    seller_proceeds: u64,
+}
+
+impl TradeProceeds {
+    fn split(total_cost: u64, trading_fee_bps: u64, royalty_bps: u64) -> Result<Self> {
+        let platform_fee = total_cost
+            .checked_mul(trading_fee_bps)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let royalty = total_cost
+            .checked_mul(royalty_bps)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let seller_proceeds = total_cost
+            .checked_sub(platform_fee)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_sub(royalty)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(Self {
+            platform_fee,
+            royalty,
+            seller_proceeds,
+        })
+    }
+}
+
+/// Mint property tokens with the property's PDA mint authority. The mint supply can never
+/// exceed `total_tokens`.
+#[allow(clippy::too_many_arguments)]
+fn mint_property_tokens<'info>(
+    token_program: &Program<'info, Token>,
+    token_mint: &Account<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    property_key: &Pubkey,
+    bump: u8,
+    total_tokens: u64,
+    amount: u64,
+) -> Result<()> {
+    let supply = token_mint
+        .supply
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(supply <= total_tokens, ErrorCode::InsufficientTokens);
+
+    let seeds = &[b"mint_authority", property_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = MintTo {
+        mint: token_mint.to_account_info(),
+        to: destination.clone(),
+        authority: mint_authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::mint_to(cpi_ctx, amount)
+}
+
+/// Move a rental income distribution out of the distributor's wallet: the platform fee to the
+/// treasury and the rest into the property's income vault
+fn fund_rental_income<'info>(
+    payer: &Signer<'info>,
+    treasury: &mut Account<'info, PlatformTreasury>,
+    income_vault: &mut Account<'info, IncomeVault>,
+    platform_fee: u64,
+    distributable_income: u64,
+) -> Result<()> {
+    for (recipient, amount) in [
+        (treasury.to_account_info(), platform_fee),
+        (income_vault.to_account_info(), distributable_income),
+    ] {
+        if amount > 0 {
+            let ix = anchor_lang::solana_program::system_instruction::transfer(
+                payer.key,
+                recipient.key,
+                amount,
+            );
+            anchor_lang::solana_program::program::invoke(&ix, &[payer.to_account_info(), recipient])?;
+        }
+    }
+
+    treasury.total_collected = treasury
+        .total_collected
+        .checked_add(platform_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    income_vault.total_deposited = income_vault
+        .total_deposited
+        .checked_add(distributable_income)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Move a rental income distribution paid in the property's income mint: the platform fee to
+/// the treasury's token account and the rest into the income token vault
+#[allow(clippy::too_many_arguments)]
+fn fund_rental_income_tokens<'info>(
+    token_program: &Program<'info, Token>,
+    payer: &Signer<'info>,
+    source: &Account<'info, TokenAccount>,
+    treasury_token_account: Option<&Account<'info, TokenAccount>>,
+    income_token_vault: &Account<'info, TokenAccount>,
+    income_vault: &mut Account<'info, IncomeVault>,
+    platform_fee: u64,
+    distributable_income: u64,
+) -> Result<()> {
+    if platform_fee > 0 {
+        let treasury_token_account = treasury_token_account.ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts = Transfer {
+            from: source.to_account_info(),
+            to: treasury_token_account.to_account_info(),
+            authority: payer.to_account_info(),
+        };
+        token::transfer(CpiContext::new(token_program.to_account_info(), cpi_accounts), platform_fee)?;
+    }
+    if distributable_income > 0 {
+        let cpi_accounts = Transfer {
+            from: source.to_account_info(),
+            to: income_token_vault.to_account_info(),
+            authority: payer.to_account_info(),
+        };
+        token::transfer(CpiContext::new(token_program.to_account_info(), cpi_accounts), distributable_income)?;
+    }
+
+    income_vault.total_deposited = income_vault
+        .total_deposited
+        .checked_add(distributable_income)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Pay rental income out of a property's income token vault, signed by the vault PDA
+fn pay_income_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    income_token_vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    property_key: &Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"income_token_vault", property_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: income_token_vault.clone(),
+        to: destination.clone(),
+        authority: income_token_vault.clone(),
+    };
+    token::transfer(CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer), amount)
+}
+
+/// Next account from a variable-length remaining_accounts layout
+fn next_remaining_account<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    next_account: &mut usize,
+) -> Result<&'a AccountInfo<'info>> {
+    let account = remaining_accounts
+        .get(*next_account)
+        .ok_or(ErrorCode::InvalidAccountsLength)?;
+    *next_account += 1;
+    Ok(account)
+}
+
+/// Pay a secondary trade from the buyer's wallet via system transfers
+fn pay_trade_from_wallet<'info>(
+    buyer: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    property_owner: &AccountInfo<'info>,
+    proceeds: &TradeProceeds,
+) -> Result<()> {
+    for (recipient, amount) in [
+        (seller, proceeds.seller_proceeds),
+        (treasury, proceeds.platform_fee),
+        (property_owner, proceeds.royalty),
+    ] {
+        if amount > 0 {
+            let ix = anchor_lang::solana_program::system_instruction::transfer(
+                buyer.key,
+                recipient.key,
+                amount,
+            );
+            anchor_lang::solana_program::program::invoke(&ix, &[buyer.clone(), recipient.clone()])?;
+        }
+    }
+    Ok(())
+}
+
+/// Pay a secondary trade out of a program-owned account holding the buyer's escrowed SOL
+fn pay_trade_from_escrow<'info>(
+    escrow: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    property_owner: &AccountInfo<'info>,
+    proceeds: &TradeProceeds,
+) -> Result<()> {
+    for (recipient, amount) in [
+        (seller, proceeds.seller_proceeds),
+        (treasury, proceeds.platform_fee),
+        (property_owner, proceeds.royalty),
+    ] {
+        if amount > 0 {
+            **escrow.try_borrow_mut_lamports()? -= amount;
+            **recipient.try_borrow_mut_lamports()? += amount;
+        }
+    }
+    Ok(())
+}
+
+/// Integer square root (floor) using Newton's method
+fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Check a Merkle proof built with sorted-pair keccak hashing and 0x01-prefixed inner nodes
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        let (left, right) = if computed <= *node { (computed, *node) } else { (*node, computed) };
+        computed = anchor_lang::solana_program::keccak::hashv(&[&[1u8], &left, &right]).to_bytes();
+    }
+    computed == root
+}
+
+/// Ring buffer of observations for one price series (SOL/USD or a property valuation)
+#[account]
+pub struct PriceHistory {
+    pub subject: Pubkey,
+    pub head: u16,                  // Next slot to overwrite once the buffer is full
+    pub observations: Vec<Observation>,
+    pub bump: u8,
+}
+
+impl PriceHistory {
+    pub const CAPACITY: usize = 64;
+    pub const SPACE: usize = 8 + 32 + 2 + 4 + Self::CAPACITY * Observation::SIZE + 1;
+
+    pub fn record(&mut self, timestamp: i64, value: u64, round_id: u64) {
+        let observation = Observation {
+            timestamp,
+            value,
+            round_id,
+        };
+        if self.observations.len() < Self::CAPACITY {
+            self.observations.push(observation);
+        } else {
+            self.observations[self.head as usize] = observation;
+            self.head = ((self.head as usize + 1) % Self::CAPACITY) as u16;
+        }
+    }
+
+    /// Observations from oldest to newest
+    fn chronological(&self) -> impl Iterator<Item = &Observation> {
+        let (newer, older) = self.observations.split_at(self.head as usize);
+        older.iter().chain(newer.iter())
+    }
+
+    /// Time-weighted average over [now - window, now]. Each observation holds until the next
+    /// one; if the history is shorter than the window, the average covers what is recorded.
+    pub fn twap(&self, now: i64, window: i64) -> Result<u64> {
+        let latest = self
+            .chronological()
+            .last()
+            .ok_or(ErrorCode::NoPriceHistory)?;
+        let window_start = now.saturating_sub(window);
+
+        let mut weighted_sum = 0u128;
+        let mut total_time = 0u128;
+        let mut observations = self.chronological().peekable();
+        while let Some(observation) = observations.next() {
+            let segment_end = observations.peek().map_or(now, |next| next.timestamp).min(now);
+            let segment_start = observation.timestamp.max(window_start);
+            if segment_end > segment_start {
+                let duration = (segment_end - segment_start) as u128;
+                weighted_sum = weighted_sum
+                    .checked_add(
+                        (observation.value as u128)
+                            .checked_mul(duration)
+                            .ok_or(ErrorCode::MathOverflow)?,
+                    )
+                    .ok_or(ErrorCode::MathOverflow)?;
+                total_time += duration;
+            }
+        }
+
+        if total_time == 0 {
+            return Ok(latest.value);
+        }
+        Ok((weighted_sum / total_time) as u64)
+    }
+}
+
+/// Oracle feeds aggregated into one price series (SOL/USD or a property valuation)
+#[account]
+pub struct OracleConfig {
+    pub subject: Pubkey,
+    pub sources: Vec<Pubkey>,
+    pub min_sources: u8,
+    pub max_deviation_bps: u64,
+    pub bump: u8,
+}
+
+impl OracleConfig {
+    pub const MAX_SOURCES: usize = 5;
+    pub const SPACE: usize = 8 + 32 + 4 + 32 * Self::MAX_SOURCES + 1 + 8 + 1;
+
+    /// Median of the fresh, confident readings among `feeds`. Stale or low-confidence
+    /// feeds are skipped; at least `min_sources` readings are required.
+    pub fn aggregate(
+        &self,
+        feeds: &[AccountInfo],
+        platform_state: &PlatformState,
+        now: i64,
+    ) -> Result<OracleReading> {
+        let mut readings: Vec<OracleReading> = Vec::with_capacity(feeds.len());
+        for (i, feed) in feeds.iter().enumerate() {
+            require!(self.sources.contains(feed.key), ErrorCode::InvalidOracleFeed);
+            require!(feed.owner == &platform_state.oracle_program, ErrorCode::InvalidOracleFeed);
+            require!(
+                !feeds[..i].iter().any(|f| f.key == feed.key),
+                ErrorCode::InvalidOracleFeed
+            );
+
+            if let Ok(reading) = OracleFeed::load(feed)?.validated_reading(now, platform_state) {
+                readings.push(reading);
+            }
+        }
+        require!(
+            readings.len() >= self.min_sources as usize,
+            ErrorCode::InsufficientOracleSources
+        );
+
+        readings.sort_by_key(|r| r.value);
+        let mid = readings.len() / 2;
+        let value = if readings.len() % 2 == 1 {
+            readings[mid].value
+        } else {
+            ((readings[mid - 1].value as u128 + readings[mid].value as u128) / 2) as u64
+        };
+
+        Ok(OracleReading {
+            value,
+            round_id: readings.iter().map(|r| r.round_id).max().unwrap_or_default(),
+            // The oldest reading used determines how fresh the aggregate is
+            publish_time: readings.iter().map(|r| r.publish_time).min().unwrap_or_default(),
+        })
+    }
+
+    /// Whether moving from `old_value` to `new_value` stays within the deviation threshold
+    pub fn within_deviation(&self, old_value: u64, new_value: u64) -> Result<bool> {
+        if old_value == 0 {
+            return Ok(true);
+        }
+        let deviation_bps = (old_value.abs_diff(new_value) as u128)
+            .checked_mul(10000)
+            .ok_or(ErrorCode::MathOverflow)?
+            / old_value as u128;
+        Ok(deviation_bps <= self.max_deviation_bps as u128)
+    }
+}
+
+/// Price feed account layout read by the program. Feeds must be owned by
+/// `PlatformState.oracle_program`; a mock program writing this layout is enough for tests.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OracleFeed {
+    pub magic: u32,
+    pub price: i64,
+    pub confidence: u64,
+    pub exponent: i32,
+    pub round_id: u64,
+    pub publish_time: i64,
+}
+
+/// A validated oracle reading normalized to 8 decimals
+pub struct OracleReading {
+    pub value: u64,
+    pub round_id: u64,
+    pub publish_time: i64,
+}
+
+impl OracleFeed {
+    pub const MAGIC: u32 = 0x5245_4f46; // "REOF"
+    pub const DECIMALS: i32 = 8;
+
+    pub fn load(feed: &AccountInfo) -> Result<Self> {
+        let data = feed.try_borrow_data()?;
+        let oracle_feed = OracleFeed::deserialize(&mut &data[..])
+            .map_err(|_| error!(ErrorCode::InvalidOracleFeed))?;
+        require!(oracle_feed.magic == Self::MAGIC, ErrorCode::InvalidOracleFeed);
+        Ok(oracle_feed)
+    }
+
+    /// Reject non-positive, stale or low-confidence rounds and normalize to 8 decimals
+    pub fn validated_reading(&self, now: i64, platform_state: &PlatformState) -> Result<OracleReading> {
+        require!(self.price > 0, ErrorCode::InvalidOracleFeed);
+        require!(
+            now.saturating_sub(self.publish_time) <= platform_state.price_staleness_window,
+            ErrorCode::StalePrice
+        );
+
+        let price = self.price as u128;
+        let confidence_bps = (self.confidence as u128)
+            .checked_mul(10000)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(price)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            confidence_bps <= platform_state.max_confidence_bps as u128,
+            ErrorCode::OracleConfidenceTooLow
+        );
+
+        let shift = self.exponent + Self::DECIMALS;
+        let scale = 10u128
+            .checked_pow(shift.unsigned_abs())
+            .ok_or(ErrorCode::MathOverflow)?;
+        let value = if shift >= 0 {
+            price.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?
+        } else {
+            price / scale
+        };
+
+        Ok(OracleReading {
+            value: u64::try_from(value).map_err(|_| ErrorCode::MathOverflow)?,
+            round_id: self.round_id,
+            publish_time: self.publish_time,
+        })
+    }
+}
+
+/// Check that the instruction before the current one is an Ed25519 program instruction
+/// verifying exactly one signature by `signer` over `message`, with all data inline
+pub fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    use anchor_lang::solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked,
+    };
+
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, ErrorCode::InvalidAttestation);
+    let ix = load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    require!(
+        ix.program_id == anchor_lang::solana_program::ed25519_program::ID,
+        ErrorCode::InvalidAttestation
+    );
+
+    // Layout: num_signatures (u8), padding (u8), then one 14-byte offsets record
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidAttestation);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        ErrorCode::InvalidAttestation
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    require!(public_key == signer.as_ref(), ErrorCode::InvalidAttestation);
+    require!(signed_message == message, ErrorCode::InvalidAttestation);
+
+    Ok(())
+}
+
+// Account structures - simplified to reduce stack usage
+#[account]
+pub struct PlatformState {
+    pub authority: Pubkey,
+    pub platform_fee: u64,
+    pub governance_threshold: u64,
+    pub total_properties: u64,
+    pub total_value_locked: u64,
+    pub sol_usd_price: u64,
+    pub last_price_update: i64,
+    pub trading_fee: u64,           // Basis points charged on secondary trades
+    pub price_staleness_window: i64, // Max age of sol_usd_price and oracle readings
+    pub oracle_program: Pubkey,     // Program that must own every oracle feed account
+    pub max_confidence_bps: u64,
+    pub twap_window: i64,           // SOL/USD TWAP window for USD-priced trades; 0 = spot price
+    pub value_locked_by_type: [u64; PropertyType::COUNT], // TVL of active properties, indexed by PropertyType
+}
+
+impl PlatformState {
+    pub const DEFAULT_PRICE_STALENESS_WINDOW: i64 = 3600;
+    pub const DEFAULT_MAX_CONFIDENCE_BPS: u64 = 200;
+
+    /// Move a property's contribution to TVL from `old_value` to `new_value`, keeping the
+    /// total and the per-type breakdown in step
+    pub fn adjust_value_locked(
+        &mut self,
+        property_type: &PropertyType,
+        old_value: u64,
+        new_value: u64,
+    ) -> Result<()> {
+        let by_type = &mut self.value_locked_by_type[property_type.index()];
+        *by_type = by_type
+            .checked_sub(old_value)
+            .and_then(|v| v.checked_add(new_value))
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_value_locked = self.total_value_locked
+            .checked_sub(old_value)
+            .and_then(|v| v.checked_add(new_value))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Convert a USD amount (8 decimals) to lamports at the stored SOL/USD price, or at
+    /// its TWAP when `twap_window` is configured
+    pub fn usd_to_lamports(
+        &self,
+        usd_amount: u64,
+        now: i64,
+        sol_price_history: Option<&PriceHistory>,
+    ) -> Result<u64> {
+        require!(self.sol_usd_price > 0, ErrorCode::StalePrice);
+        require!(
+            now.saturating_sub(self.last_price_update) <= self.price_staleness_window,
+            ErrorCode::StalePrice
+        );
+
+        let sol_usd_price = if self.twap_window > 0 {
+            sol_price_history
+                .ok_or(ErrorCode::MissingPriceHistory)?
+                .twap(now, self.twap_window)?
+        } else {
+            self.sol_usd_price
+        };
+        require!(sol_usd_price > 0, ErrorCode::StalePrice);
+
+        let lamports = (usd_amount as u128)
+            .checked_mul(anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(sol_usd_price as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        u64::try_from(lamports).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+#[account]
+pub struct PlatformTreasury {
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Property {
+    pub property_id: String,        // 32 max
+    pub owner: Pubkey,
+    pub total_tokens: u64,
+    pub tokens_sold: u64,
+    pub token_price: u64,
+    pub property_address: String,   // 100 max
+    pub property_type: PropertyType,
+    pub legal_document_hash: String, // 32 max
+    pub total_rental_income: u64,
+    pub last_income_distribution: i64,
+    pub is_active: bool,
+    pub token_mint: Pubkey,
+    pub property_valuation: u64,
+    pub last_valuation_update: i64,
+    pub kyc_required: bool,
+    pub expected_rental_yield: u64,
+    pub property_vault: Pubkey,
     pub is_for_sale: bool,
     pub asking_price: u64,
     pub market_valuation: u64,
     pub sale_initiated_at: i64,
     pub final_sale_price: u64,
     pub sale_completed_at: i64,
+    pub royalty_bps: u64,           // Paid to the owner on secondary trades
+    pub payment_mint: Option<Pubkey>, // SPL mint for primary sales; None = SOL
+    pub price_in_usd: bool,         // token_price and listing prices are USD with 8 decimals
+    pub appraiser: Pubkey,          // Appraiser behind the latest attested valuation
+    pub appraisal_report_hash: [u8; 32],
+    pub appraisal_effective_date: i64,
+    pub in_funding_round: bool,     // Direct purchases are paused while a round is pending
+    pub min_purchase: u64,          // Tokens per purchase; 0 = no minimum
+    pub max_purchase: u64,          // Tokens held per investor; 0 = no maximum
+    pub max_ownership_bps: u64,     // Share of total_tokens held per investor; 0 = no maximum
+    pub public_sale_starts_at: i64, // Whitelist-only primary sales before this time
+    pub lockup_cliff: i64,          // Seconds after purchase before any tokens vest
+    pub lockup_duration: i64,       // Seconds until purchased tokens fully vest; 0 = no lock-up
+    pub income_per_token: u128,     // Cumulative distributable income per token, scaled by INCOME_PRECISION
+    pub income_mint: Option<Pubkey>, // SPL mint rental income is paid in; None = SOL
+    pub distribution_count: u64,    // Push-mode income distributions opened so far
+}
+
+impl Property {
+    pub const INCOME_PRECISION: u128 = 1_000_000_000_000;
+
+    /// Add distributable income to the per-token index and return the index increase
+    pub fn accrue_income(&mut self, distributable_income: u64) -> Result<u128> {
+        require!(self.tokens_sold > 0, ErrorCode::NoTokensIssued);
+        let delta = (distributable_income as u128)
+            .checked_mul(Self::INCOME_PRECISION)
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.tokens_sold as u128;
+        self.income_per_token = self
+            .income_per_token
+            .checked_add(delta)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_rental_income = self
+            .total_rental_income
+            .checked_add(distributable_income)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(delta)
+    }
+
+    /// Bytes an appraiser signs off-chain for `submit_signed_appraisal`
+    pub fn appraisal_message(
+        property: &Pubkey,
+        valuation: u64,
+        report_hash: &[u8; 32],
+        effective_date: i64,
+    ) -> Vec<u8> {
+        let mut message = Vec::with_capacity(9 + 32 + 8 + 32 + 8);
+        message.extend_from_slice(b"appraisal");
+        message.extend_from_slice(property.as_ref());
+        message.extend_from_slice(&valuation.to_le_bytes());
+        message.extend_from_slice(report_hash);
+        message.extend_from_slice(&effective_date.to_le_bytes());
+        message
+    }
+
+    /// Store an attested valuation and return the previous one. Reports must be newer than
+    /// the current one, which also stops a signed report from being replayed.
+    pub fn apply_appraisal(
+        &mut self,
+        appraiser: Pubkey,
+        valuation: u64,
+        report_hash: [u8; 32],
+        effective_date: i64,
+        now: i64,
+    ) -> Result<u64> {
+        require!(self.is_active, ErrorCode::PropertyNotActive);
+        require!(valuation > 0, ErrorCode::InvalidValuation);
+        require!(
+            effective_date <= now && effective_date > self.appraisal_effective_date,
+            ErrorCode::InvalidAppraisalDate
+        );
+
+        let old_valuation = self.property_valuation;
+        self.property_valuation = valuation;
+        self.last_valuation_update = effective_date;
+        self.appraiser = appraiser;
+        self.appraisal_report_hash = report_hash;
+        self.appraisal_effective_date = effective_date;
+        Ok(old_valuation)
+    }
+
+    /// Enforce the offering rules on a primary purchase of `amount` tokens that leaves the
+    /// investor holding `holding_after`
+    pub fn check_offering_rules(
+        &self,
+        amount: u64,
+        holding_after: u64,
+        now: i64,
+        whitelist_entry: Option<&WhitelistEntry>,
+    ) -> Result<()> {
+        require!(amount >= self.min_purchase, ErrorCode::BelowMinimumPurchase);
+        require!(
+            self.max_purchase == 0 || holding_after <= self.max_purchase,
+            ErrorCode::PurchaseLimitExceeded
+        );
+        if self.max_ownership_bps > 0 {
+            let max_holding = (self.total_tokens as u128)
+                .checked_mul(self.max_ownership_bps as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / 10000;
+            require!(
+                holding_after as u128 <= max_holding,
+                ErrorCode::OwnershipLimitExceeded
+            );
+        }
+
+        if now < self.public_sale_starts_at {
+            let entry = whitelist_entry.ok_or(ErrorCode::NotWhitelisted)?;
+            require!(
+                entry.allocation == 0 || holding_after <= entry.allocation,
+                ErrorCode::PurchaseLimitExceeded
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Early-access grant for an investor in a property's offering
+#[account]
+pub struct WhitelistEntry {
+    pub property: Pubkey,
+    pub investor: Pubkey,
+    pub allocation: u64,            // Max tokens held during early access; 0 = no extra cap
+    pub bump: u8,
+}
+
+/// Licensed appraiser allowed to attest property valuations
+#[account]
+pub struct Appraiser {
+    pub appraiser: Pubkey,
+    pub license_id: String,         // 32 max
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+/// Rental income for one period, claimable against a Merkle root of a holder snapshot
+#[account]
+pub struct MerkleDistribution {
+    pub property: Pubkey,
+    pub distribution_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,          // Distributable income backing the leaves
+    pub total_claimed: u64,
+    pub num_leaves: u32,
+    pub income_mint: Option<Pubkey>, // Property income mint when the distribution was posted
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl MerkleDistribution {
+    /// Keeps the claimed bitmap within the 10 KiB limit for accounts created by the program
+    pub const MAX_LEAVES: u32 = 80_000;
+
+    /// Leaf hash for `index`: keccak(0x00 || index LE || investor || amount LE)
+    pub fn leaf(index: u32, investor: &Pubkey, amount: u64) -> [u8; 32] {
+        anchor_lang::solana_program::keccak::hashv(&[
+            &[0u8],
+            &index.to_le_bytes(),
+            investor.as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+/// One bit per Merkle leaf, set once that leaf has been claimed
+#[account]
+pub struct ClaimedBitmap {
+    pub distribution: Pubkey,
+    pub bits: Vec<u8>,
+}
+
+impl ClaimedBitmap {
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.bits[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.bits[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+/// Progress of a push-mode rental income distribution, which may span many transactions
+#[account]
+pub struct IncomeDistribution {
+    pub property: Pubkey,
+    pub round_id: u64,
+    pub sequence: u64,              // Property distribution_count when the round was opened
+    pub total_income: u64,
+    pub distributable_income: u64,
+    pub investors_paid: u64,
+    pub amount_paid: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// Holds a property's distributed rental income, separate from sale proceeds in the vault PDA
+#[account]
+pub struct IncomeVault {
+    pub property: Pubkey,
+    pub total_deposited: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct InvestorRecord {
+    pub investor: Pubkey,
+    pub property: Pubkey,
+    pub tokens_owned: u64,
+    pub total_invested: u64,        // Lamports spent on tokens
+    pub total_claimed: u64,
+    pub last_claim_time: i64,
+    pub lockups: [Lockup; InvestorRecord::MAX_LOCKUPS], // Primary-sale lock-ups, one per schedule
+    pub income_checkpoint: u128,    // Property income_per_token at the last settlement
+    pub pending_income: u64,        // Settled but unclaimed rental income
+    pub last_distribution_paid: u64, // Sequence of the last push distribution that paid this record
+    pub total_invested_payment: u64, // Primary-sale spend in the property's payment mint base units
+    pub tokens_escrowed: u64,       // Owned tokens held in listing, order and auction escrows
+}
+
+impl InvestorRecord {
+    pub const MAX_LOCKUPS: usize = 8;
+    pub const SPACE: usize =
+        8 + 32 + 32 + 8 + 8 + 8 + 8 + Lockup::SIZE * Self::MAX_LOCKUPS + 16 + 8 + 8 + 8 + 8;
+
+    /// Accrue income earned by the current holding since the last checkpoint. Must run before
+    /// every change to `tokens_owned` so income follows the tokens held at each distribution.
+    pub fn settle_income(&mut self, income_per_token: u128) -> Result<()> {
+        let accrued = (self.tokens_owned as u128)
+            .checked_mul(
+                income_per_token
+                    .checked_sub(self.income_checkpoint)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?
+            / Property::INCOME_PRECISION;
+        self.pending_income = self
+            .pending_income
+            .checked_add(u64::try_from(accrued).map_err(|_| ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.income_checkpoint = income_per_token;
+        Ok(())
+    }
+
+    /// Tokens still locked at `now` across all lock-ups
+    pub fn locked_tokens(&self, now: i64) -> u64 {
+        self.lockups.iter().map(|lockup| lockup.locked(now)).sum()
+    }
+
+    /// Fail unless `amount` tokens are owned, out of lock-up at `now` and not already escrowed
+    pub fn require_unlocked(&self, amount: u64, now: i64) -> Result<()> {
+        require!(self.tokens_owned >= amount, ErrorCode::InsufficientTokens);
+        let free = self.tokens_owned.saturating_sub(self.tokens_escrowed);
+        require!(free >= amount, ErrorCode::InsufficientTokens);
+        require!(
+            free.saturating_sub(self.locked_tokens(now)) >= amount,
+            ErrorCode::TokensLocked
+        );
+        Ok(())
+    }
+
+    /// Reserve unlocked tokens moving into a listing, order or auction escrow
+    pub fn escrow_tokens(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.require_unlocked(amount, now)?;
+        self.tokens_escrowed = self
+            .tokens_escrowed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Release escrowed tokens that were sold out of, or returned from, an escrow
+    pub fn release_escrowed(&mut self, amount: u64) -> Result<()> {
+        self.tokens_escrowed = self
+            .tokens_escrowed
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Place newly bought tokens under their own lock-up. Purchases on the same schedule share
+    /// a slot and fully vested slots are reused. Once every slot is in use, the purchase joins
+    /// the slot ending last, along with that slot's still-locked tokens, on the later schedule.
+    pub fn add_lockup(&mut self, amount: u64, now: i64, cliff: i64, duration: i64) -> Result<()> {
+        if duration == 0 {
+            return Ok(());
+        }
+        let lockup = Lockup {
+            amount,
+            start: now,
+            cliff: now + cliff,
+            end: now + duration,
+        };
+        if let Some(slot) = self
+            .lockups
+            .iter_mut()
+            .find(|l| {
+                l.amount > 0 && l.start == lockup.start && l.cliff == lockup.cliff && l.end == lockup.end
+            })
+        {
+            slot.amount = slot.amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+            return Ok(());
+        }
+        if let Some(slot) = self.lockups.iter_mut().find(|l| l.locked(now) == 0) {
+            *slot = lockup;
+            return Ok(());
+        }
+        let latest = (0..Self::MAX_LOCKUPS)
+            .max_by_key(|&i| self.lockups[i].end)
+            .unwrap_or_default();
+        let slot = &mut self.lockups[latest];
+        let (cliff, end) = if lockup.end >= slot.end {
+            (lockup.cliff, lockup.end)
+        } else {
+            (slot.cliff, slot.end)
+        };
+        // Vesting restarts now so none of the merged tokens unlock early
+        *slot = Lockup {
+            amount: slot
+                .locked(now)
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?,
+            start: now,
+            cliff,
+            end,
+        };
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Proposal {
+    pub property: Pubkey,
+    pub proposer: Pubkey,
+    pub title: String,              // 50 max
+    pub description: String,        // 200 max
+    pub proposal_type: ProposalType,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub total_votes: u64,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub executed: bool,
+    pub passed: bool,
+}
+
+#[account]
+pub struct VoteRecord {
+    pub voter: Pubkey,
+    pub proposal: Pubkey,
+    pub vote_for: bool,
+    pub voting_power: u64,
+    pub has_voted: bool,
+    pub voted_at: i64,
+}
+
+#[account]
+pub struct KycRecord {
+    pub user: Pubkey,
+    pub is_verified: bool,
+    pub updated_at: i64,
+    pub verification_provider: String,
+    pub round_id: u64,
+}
+
+#[account]
+pub struct MarketListing {
+    pub seller: Pubkey,
+    pub property: Pubkey,
+    pub amount: u64,
+    pub price_per_token: u64,
+    pub total_price: u64,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub market_price_reference: u64,
+    pub expires_at: Option<i64>,
+    pub price_in_usd: bool,         // price_per_token is USD with 8 decimals, fixed at listing time
+}
+
+#[account]
+pub struct MarketBid {
+    pub bidder: Pubkey,
+    pub property: Pubkey,
+    pub bid_id: u64,
+    pub amount: u64,
+    pub price_per_token: u64,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct OrderBook {
+    pub property: Pubkey,
+    pub token_mint: Pubkey,
+    pub next_order_id: u64,
+    pub bids: Vec<Order>,           // Sorted by price descending, then time
+    pub asks: Vec<Order>,           // Sorted by price ascending, then time
+    pub bump: u8,
+}
+
+impl OrderBook {
+    pub const MAX_ORDERS_PER_SIDE: usize = 32;
+    /// Keeps one owner from filling a side of the book with dust orders
+    pub const MAX_ORDERS_PER_OWNER: usize = 4;
+    pub const MIN_ORDER_NOTIONAL: u64 = 100_000_000; // 0.1 SOL
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + (4 + Self::MAX_ORDERS_PER_SIDE * Order::SIZE) * 2 + 1;
+
+    /// Insert an order behind all orders at the same or better price
+    pub fn insert_order(&mut self, side: &OrderSide, order: Order) -> Result<()> {
+        let orders = match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        };
+        require!(orders.len() < Self::MAX_ORDERS_PER_SIDE, ErrorCode::OrderBookFull);
+        let notional = order
+            .price
+            .checked_mul(order.amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(notional >= Self::MIN_ORDER_NOTIONAL, ErrorCode::OrderTooSmall);
+        require!(
+            orders.iter().filter(|o| o.owner == order.owner).count() < Self::MAX_ORDERS_PER_OWNER,
+            ErrorCode::TooManyOpenOrders
+        );
+
+        let index = match side {
+            OrderSide::Bid => orders.iter().position(|o| o.price < order.price),
+            OrderSide::Ask => orders.iter().position(|o| o.price > order.price),
+        }
+        .unwrap_or(orders.len());
+        orders.insert(index, order);
+
+        Ok(())
+    }
+
+    pub fn remove_order(&mut self, order_id: u64) -> Option<(OrderSide, Order)> {
+        if let Some(index) = self.bids.iter().position(|o| o.order_id == order_id) {
+            return Some((OrderSide::Bid, self.bids.remove(index)));
+        }
+        if let Some(index) = self.asks.iter().position(|o| o.order_id == order_id) {
+            return Some((OrderSide::Ask, self.asks.remove(index)));
+        }
+        None
+    }
+
+    /// Whether a new order would cross a resting order from the same owner
+    pub fn would_self_trade(&self, side: &OrderSide, owner: Pubkey, price: u64) -> bool {
+        match side {
+            OrderSide::Bid => self.asks.iter().any(|o| o.owner == owner && o.price <= price),
+            OrderSide::Ask => self.bids.iter().any(|o| o.owner == owner && o.price >= price),
+        }
+    }
+}
+
+#[account]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub property: Pubkey,
+    pub auction_id: u64,
+    pub auction_type: AuctionType,
+    pub amount: u64,
+    pub start_price: u64,
+    pub reserve_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,           // Per token
+    pub is_settled: bool,
+    pub bump: u8,
+}
+
+impl Auction {
+    /// Dutch auction price per token, declining linearly from start_price to reserve_price
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        if now <= self.start_time {
+            return Ok(self.start_price);
+        }
+        if now >= self.end_time {
+            return Ok(self.reserve_price);
+        }
+        let elapsed = (now - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let decline = ((self.start_price - self.reserve_price) as u128)
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(duration)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        Ok(self.start_price - decline)
+    }
+}
+
+#[account]
+pub struct LiquidityPool {
+    pub property: Pubkey,
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub fee_bps: u64,
+    pub platform_fee_share_bps: u64,
+    pub platform_fees_token: u64,   // Held in token_vault but excluded from reserves
+    pub platform_fees_quote: u64,   // Held in quote_vault but excluded from reserves
+    pub income_per_lp_share: u128,  // Pool rental income per LP token, scaled by INCOME_PRECISION
+    pub income_indexed: u64,        // Part of the pool record's pending income already in the index
+    pub bump: u8,
+}
+
+impl LiquidityPool {
+    /// LP tokens locked in the pool on the first deposit
+    pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+    /// Pool reserves, excluding platform fees awaiting collection
+    pub fn reserves(&self, token_vault_amount: u64, quote_vault_amount: u64) -> Result<(u64, u64)> {
+        let token_reserve = token_vault_amount
+            .checked_sub(self.platform_fees_token)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let quote_reserve = quote_vault_amount
+            .checked_sub(self.platform_fees_quote)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok((token_reserve, quote_reserve))
+    }
+
+    /// Spread rental income newly settled on the pool's record over the LP tokens held by
+    /// providers. Must run before every change to the LP supply.
+    pub fn accrue_lp_income(&mut self, pool_record: &InvestorRecord, lp_supply: u64) -> Result<()> {
+        let lp_shares = lp_supply.saturating_sub(Self::MINIMUM_LIQUIDITY);
+        if lp_shares == 0 {
+            return Ok(());
+        }
+        let new_income = pool_record
+            .pending_income
+            .checked_sub(self.income_indexed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.income_per_lp_share = self
+            .income_per_lp_share
+            .checked_add(
+                (new_income as u128)
+                    .checked_mul(Property::INCOME_PRECISION)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    / lp_shares as u128,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.income_indexed = pool_record.pending_income;
+        Ok(())
+    }
+}
+
+/// A liquidity provider's share of the rental income earned by a pool's tokens. LP tokens stay
+/// frozen in the provider's associated account, so its balance is the provider's position.
+#[account]
+pub struct LpPosition {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub income_checkpoint: u128,    // Pool income_per_lp_share at the last settlement
+    pub pending_income: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+impl LpPosition {
+    pub const SPACE: usize = 8 + 32 + 32 + 16 + 8 + 8 + 1;
+
+    /// Accrue income earned by `lp_balance` since the last checkpoint. Must run before every
+    /// change to the provider's LP balance.
+    pub fn settle_income(&mut self, income_per_lp_share: u128, lp_balance: u64) -> Result<()> {
+        let accrued = (lp_balance as u128)
+            .checked_mul(
+                income_per_lp_share
+                    .checked_sub(self.income_checkpoint)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?
+            / Property::INCOME_PRECISION;
+        self.pending_income = self
+            .pending_income
+            .checked_add(u64::try_from(accrued).map_err(|_| ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.income_checkpoint = income_per_lp_share;
+        Ok(())
+    }
+}
+
+/// Offering phase for a property's primary sale; escrows SOL itself when there is no payment mint
+#[account]
+pub struct FundingRound {
+    pub property: Pubkey,
+    pub round_id: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub soft_cap: u64,              // Tokens
+    pub hard_cap: u64,              // Tokens
+    pub tokens_committed: u64,
+    pub total_raised: u64,          // Lamports or payment mint units
+    pub payment_mint: Option<Pubkey>,
+    pub status: FundingRoundStatus,
+    pub income_per_token: u128,     // Property index when the round succeeded
+    pub bump: u8,
+}
+
+#[account]
+pub struct Contribution {
+    pub funding_round: Pubkey,
+    pub contributor: Pubkey,
+    pub tokens: u64,
+    pub amount_paid: u64,
+    pub bump: u8,
+}
+
+/// Dutch-auction primary offering; escrows SOL itself under uniform clearing
+#[account]
+pub struct DutchOffering {
+    pub property: Pubkey,
+    pub offering_id: u64,
+    pub start_price: u64,           // Lamports per token
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub tokens_offered: u64,
+    pub tokens_sold: u64,
+    pub total_raised: u64,
+    pub last_price: u64,
+    pub clearing_price: u64,        // Set on finalization
+    pub uniform_clearing: bool,
+    pub is_finalized: bool,
+    pub bump: u8,
+}
+
+impl DutchOffering {
+    /// Price per token, declining linearly from start_price to floor_price
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        if now <= self.start_time {
+            return Ok(self.start_price);
+        }
+        if now >= self.end_time {
+            return Ok(self.floor_price);
+        }
+        let elapsed = (now - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let decline = ((self.start_price - self.floor_price) as u128)
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(duration)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        Ok(self.start_price - decline)
+    }
+}
+
+#[account]
+pub struct DutchOfferingPurchase {
+    pub offering: Pubkey,
+    pub buyer: Pubkey,
+    pub tokens: u64,
+    pub amount_paid: u64,
+    pub bump: u8,
+}
+
+impl MarketListing {
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expiry) if now >= expiry)
+    }
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum PropertyType {
+    Residential,
+    Commercial,
+    Industrial,
+    Mixed,
+}
+
+impl PropertyType {
+    pub const COUNT: usize = 4;
+
+    pub fn index(&self) -> usize {
+        match self {
+            PropertyType::Residential => 0,
+            PropertyType::Commercial => 1,
+            PropertyType::Industrial => 2,
+            PropertyType::Mixed => 3,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum AuctionType {
+    English,
+    Dutch,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum SwapDirection {
+    QuoteToToken,
+    TokenToQuote,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum FundingRoundStatus {
+    Active,
+    Succeeded,
+    Failed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ProposalType {
+    RenovationApproval,
+    TenantApproval,
+    PropertySale,
+    ManagementChange,
+}
+
+// Order book entries
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Order {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub amount: u64,
+    pub created_at: i64,
+}
+
+impl Order {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8;
+}
+
+// Price history entries
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub value: u64,
+    pub round_id: u64,
+}
+
+impl Observation {
+    pub const SIZE: usize = 8 + 8 + 8;
+}
+
+// Lock-up schedule for one primary purchase
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Lockup {
+    pub amount: u64,
+    pub start: i64,
+    pub cliff: i64,                 // Nothing vests before this time
+    pub end: i64,
+}
+
+impl Lockup {
+    pub const SIZE: usize = 8 + 8 + 8 + 8;
+
+    /// Tokens still locked at `now`: everything before the cliff, then vesting linearly
+    /// from `start` until `end`
+    pub fn locked(&self, now: i64) -> u64 {
+        if self.amount == 0 || now >= self.end {
+            return 0;
+        }
+        if now < self.cliff {
+            return self.amount;
+        }
+        let elapsed = (now - self.start) as u128;
+        let duration = (self.end - self.start) as u128;
+        let vested = self.amount as u128 * elapsed / duration;
+        self.amount - vested as u64
+    }
+}
+
+// Data structures for batch operations
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenTransfer {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct KycUpdate {
+    pub user: Pubkey,
+    pub is_verified: bool,
+    pub chainlink_round_id: u64,
+}
+
+// Context structures
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 8 + 8 * PropertyType::COUNT,
+        seeds = [b"platform"],
+        bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 8 + 1,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, PlatformTreasury>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTradingFee<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryTokens<'info> {
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, PlatformTreasury>,
+    #[account(
+        mut,
+        token::authority = treasury
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, PlatformTreasury>,
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProperty<'info> {
+    #[account(
+        init,
+        payer = property_owner,
+        space = 8 + 4 + 32 + 32 + 8 + 8 + 8 + 4 + 100 + 1 + 4 + 32 + 8 + 8 + 1 + 32 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 33 + 1 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 16 + 33 + 8
+    )]
+    pub property: Account<'info, Property>,
+    /// CHECK: PDA that holds mint authority so only the program can mint
+    #[account(
+        seeds = [b"mint_authority", property.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = property_owner,
+        mint::decimals = 0,
+        mint::authority = mint_authority
+    )]
+    pub token_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub property_owner: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePropertyValuation<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        seeds = [b"oracle_config", property.key().as_ref()],
+        bump = oracle_config.bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+    #[account(
+        mut,
+        seeds = [b"price_history", property.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+    // Use remaining_accounts for the oracle feeds listed in oracle_config
+}
+
+#[derive(Accounts)]
+pub struct RegisterAppraiser<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    /// CHECK: Appraiser wallet being registered
+    pub appraiser: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 4 + 32 + 1 + 8 + 1,
+        seeds = [b"appraiser", appraiser.key().as_ref()],
+        bump
+    )]
+    pub appraiser_record: Account<'info, Appraiser>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAppraiser<'info> {
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        mut,
+        seeds = [b"appraiser", appraiser_record.appraiser.as_ref()],
+        bump = appraiser_record.bump
+    )]
+    pub appraiser_record: Account<'info, Appraiser>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitAppraisal<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+    pub appraiser: Signer<'info>,
+    #[account(
+        seeds = [b"appraiser", appraiser.key().as_ref()],
+        bump = appraiser_record.bump,
+        constraint = appraiser_record.is_active @ ErrorCode::AppraiserNotActive
+    )]
+    pub appraiser_record: Account<'info, Appraiser>,
+    #[account(
+        mut,
+        seeds = [b"price_history", property.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSignedAppraisal<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        seeds = [b"appraiser", appraiser_record.appraiser.as_ref()],
+        bump = appraiser_record.bump,
+        constraint = appraiser_record.is_active @ ErrorCode::AppraiserNotActive
+    )]
+    pub appraiser_record: Account<'info, Appraiser>,
+    #[account(
+        mut,
+        seeds = [b"price_history", property.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+    /// CHECK: Instructions sysvar, read to find the Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePropertyRoyalty<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenPrice<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaymentMint<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"payment_vault", property.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = payment_vault
+    )]
+    pub payment_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPaymentVault<'info> {
+    pub property: Box<Account<'info, Property>>,
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        seeds = [b"payment_vault", property.key().as_ref()],
+        bump
+    )]
+    pub payment_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = payment_vault.mint,
+        token::authority = property.owner
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseTokens<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", buyer.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+    #[account(mut, address = property.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault", property.key().as_ref()],
+        bump
+    )]
+    pub property_vault: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Account<'info, InvestorRecord>,
+    /// CHECK: PDA mint authority, signs via seeds
+    #[account(
+        seeds = [b"mint_authority", property.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        seeds = [b"price_history", platform_state.key().as_ref()],
+        bump = sol_price_history.bump
+    )]
+    pub sol_price_history: Option<Box<Account<'info, PriceHistory>>>,
+    // Only required during the early-access window
+    #[account(
+        seeds = [b"whitelist", property.key().as_ref(), buyer.key().as_ref()],
+        bump = whitelist_entry.bump
+    )]
+    pub whitelist_entry: Option<Box<Account<'info, WhitelistEntry>>>,
+    // Payment accounts are only required when the property has a payment mint
+    #[account(
+        mut,
+        token::authority = buyer
+    )]
+    pub buyer_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"payment_vault", property.key().as_ref()],
+        bump
+    )]
+    pub payment_vault: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetIncomeMint<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    pub income_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump,
+        token::mint = income_mint,
+        token::authority = income_token_vault
+    )]
+    pub income_token_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeIncomeVault<'info> {
+    pub property: Account<'info, Property>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 1,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_vault: Account<'info, IncomeVault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeRentalIncome<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, PlatformTreasury>,
+    #[account(
+        mut,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump = income_vault.bump
+    )]
+    pub income_vault: Account<'info, IncomeVault>,
+    // Token accounts are only required when the property has an income mint
+    #[account(
+        mut,
+        token::authority = authority
+    )]
+    pub distributor_income_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = treasury
+    )]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64, merkle_root: [u8; 32], total_income: u64, num_leaves: u32)]
+pub struct CreateMerkleDistribution<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 32 + 8 + 8 + 4 + 33 + 8 + 1,
+        seeds = [b"merkle_distribution", property.key().as_ref(), &distribution_id.to_le_bytes()],
+        bump
+    )]
+    pub distribution: Box<Account<'info, MerkleDistribution>>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + (num_leaves as usize).div_ceil(8),
+        seeds = [b"claimed_bitmap", distribution.key().as_ref()],
+        bump
+    )]
+    pub claimed_bitmap: Box<Account<'info, ClaimedBitmap>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, PlatformTreasury>>,
+    #[account(
+        mut,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump = income_vault.bump
+    )]
+    pub income_vault: Account<'info, IncomeVault>,
+    // Token accounts are only required when the property has an income mint
+    #[account(
+        mut,
+        token::authority = authority
+    )]
+    pub distributor_income_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = treasury
+    )]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMerkleIncome<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"merkle_distribution", property.key().as_ref(), &distribution.distribution_id.to_le_bytes()],
+        bump = distribution.bump
+    )]
+    pub distribution: Box<Account<'info, MerkleDistribution>>,
+    #[account(
+        mut,
+        seeds = [b"claimed_bitmap", distribution.key().as_ref()],
+        bump
+    )]
+    pub claimed_bitmap: Box<Account<'info, ClaimedBitmap>>,
+    #[account(
+        mut,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump = income_vault.bump
+    )]
+    pub income_vault: Account<'info, IncomeVault>,
+    // Token accounts are only required when the distribution pays an income mint
+    #[account(
+        mut,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub investor_income_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRentalIncome<'info> {
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), investor.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Account<'info, InvestorRecord>,
+    #[account(
+        mut,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump = income_vault.bump
+    )]
+    pub income_vault: Account<'info, IncomeVault>,
+    // Token accounts are only required when the property has an income mint
+    #[account(
+        mut,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub investor_income_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        seeds = [b"investor", property.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Account<'info, InvestorRecord>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + 32 + 32 + 4 + 50 + 4 + 200 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 1
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub platform_state: Account<'info, PlatformState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteOnProposal<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(
+        seeds = [b"investor", proposal.property.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Account<'info, InvestorRecord>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + 32 + 32 + 1 + 8 + 1 + 8,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub from: Signer<'info>,
+    /// CHECK: Safe as we only use it as a key
+    pub to: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub from_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), from.key().as_ref()],
+        bump
+    )]
+    pub from_investor_record: Account<'info, InvestorRecord>,
+    #[account(
+        init_if_needed,
+        payer = from,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), to.key().as_ref()],
+        bump
+    )]
+    pub to_investor_record: Account<'info, InvestorRecord>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateKycStatus<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 1 + 8 + 4 + 20 + 8 // Added space for verification_provider and round_id
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+    /// CHECK: User whose KYC status is being updated
+    pub user: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyUserKyc<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 1 + 8 + 4 + 20 + 8
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+    /// CHECK: User whose KYC status is being verified
+    pub user: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRentalYield<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct ListTokensForSale<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 8 + 1
+    )]
+    pub market_listing: Account<'info, MarketListing>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"escrow", market_listing.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = escrow_token_account
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct BuyFromMarket<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", buyer.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = seller
+    )]
+    pub market_listing: Box<Account<'info, MarketListing>>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"escrow", market_listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = token_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, PlatformTreasury>>,
+    /// CHECK: Property owner receiving the royalty
+    #[account(mut, address = property.owner)]
+    pub property_owner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"price_history", platform_state.key().as_ref()],
+        bump = sol_price_history.bump
+    )]
+    pub sol_price_history: Option<Box<Account<'info, PriceHistory>>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = seller,
+        close = seller
+    )]
+    pub market_listing: Box<Account<'info, MarketListing>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"escrow", market_listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredListing<'info> {
+    pub property: Box<Account<'info, Property>>,
+    pub cranker: Signer<'info>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = seller,
+        close = seller
+    )]
+    pub market_listing: Box<Account<'info, MarketListing>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"escrow", market_listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    pub property: Box<Account<'info, Property>>,
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = seller
+    )]
+    pub market_listing: Box<Account<'info, MarketListing>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"escrow", market_listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(bid_id: u64)]
+pub struct PlaceBid<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", bidder.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 1,
+        seeds = [b"bid", property.key().as_ref(), bidder.key().as_ref(), &bid_id.to_le_bytes()],
+        bump
+    )]
+    pub market_bid: Box<Account<'info, MarketBid>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FillBid<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    /// CHECK: Bidder receiving the tokens, validated against the bid
+    pub bidder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = bidder,
+        seeds = [b"bid", property.key().as_ref(), bidder.key().as_ref(), &market_bid.bid_id.to_le_bytes()],
+        bump = market_bid.bump
+    )]
+    pub market_bid: Box<Account<'info, MarketBid>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = token_mint,
+        associated_token::authority = bidder
+    )]
+    pub bidder_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, PlatformTreasury>>,
+    /// CHECK: Property owner receiving the royalty
+    #[account(mut, address = property.owner)]
+    pub property_owner: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBid<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = bidder,
+        close = bidder
+    )]
+    pub market_bid: Box<Account<'info, MarketBid>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeOrderBook<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        init,
+        payer = authority,
+        space = OrderBook::SPACE,
+        seeds = [b"order_book", property.key().as_ref()],
+        bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = order_book_vault
+    )]
+    pub order_book_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", owner.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        mut,
+        has_one = property,
+        seeds = [b"order_book", property.key().as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub order_book_vault: Box<Account<'info, TokenAccount>>,
+    // Only required when placing an ask
+    #[account(
+        mut,
+        associated_token::mint = order_book.token_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Option<Box<Account<'info, InvestorRecord>>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        seeds = [b"order_book", property.key().as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub order_book_vault: Box<Account<'info, TokenAccount>>,
+    // Only required when cancelling an ask
+    #[account(
+        mut,
+        associated_token::mint = order_book.token_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Option<Box<Account<'info, InvestorRecord>>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        seeds = [b"order_book", property.key().as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub order_book_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = order_book.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Owner of the best bid, validated against the order book
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+    /// CHECK: Owner of the best ask, validated against the order book
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, PlatformTreasury>>,
+    /// CHECK: Property owner receiving the royalty
+    #[account(mut, address = property.owner)]
+    pub property_owner: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateAuction<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 33 + 8 + 1 + 1,
+        seeds = [b"auction", property.key().as_ref(), seller.key().as_ref(), &auction_id.to_le_bytes()],
+        bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = escrow_token_account
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceAuctionBid<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", bidder.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        mut,
+        has_one = property,
+        seeds = [b"auction", property.key().as_ref(), auction.seller.as_ref(), &auction.auction_id.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    /// CHECK: Current highest bidder being refunded, validated against the auction
+    #[account(mut)]
+    pub previous_bidder: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyDutchAuction<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", buyer.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = seller,
+        close = seller,
+        seeds = [b"auction", property.key().as_ref(), seller.key().as_ref(), &auction.auction_id.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = token_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, PlatformTreasury>>,
+    /// CHECK: Property owner receiving the royalty
+    #[account(mut, address = property.owner)]
+    pub property_owner: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = seller,
+        close = seller,
+        seeds = [b"auction", property.key().as_ref(), seller.key().as_ref(), &auction.auction_id.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_investor_record: Box<Account<'info, InvestorRecord>>,
+    // Winner accounts are only required when the auction has a winning bid
+    /// CHECK: Highest bidder, validated against the auction
+    pub winner: Option<UncheckedAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = winner
+    )]
+    pub winner_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), auction.highest_bidder.unwrap_or_default().as_ref()],
+        bump,
+        constraint = auction.highest_bidder.is_some() @ ErrorCode::InvalidAuctionWinner
+    )]
+    pub winner_investor_record: Option<Box<Account<'info, InvestorRecord>>>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, PlatformTreasury>>,
+    /// CHECK: Property owner receiving the royalty
+    #[account(mut, address = property.owner)]
+    pub property_owner: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 16 + 8 + 1,
+        seeds = [b"pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    #[account(address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_lp_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = pool,
+        mint::freeze_authority = pool
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_token_vault", pool.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = pool
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_quote_vault", pool.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool
+    )]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_locked_lp", pool.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = pool
+    )]
+    pub locked_lp_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", provider.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = lp_mint,
+        has_one = token_vault,
+        has_one = quote_vault,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = pool.token_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = pool.quote_mint,
+        token::authority = provider
+    )]
+    pub provider_quote_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_lp_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = LpPosition::SPACE,
+        seeds = [b"lp_position", pool.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        mut,
+        seeds = [b"pool_locked_lp", pool.key().as_ref()],
+        bump
+    )]
+    pub locked_lp_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", provider.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = lp_mint,
+        has_one = token_vault,
+        has_one = quote_vault,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = pool.token_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = pool.quote_mint,
+        token::authority = provider
+    )]
+    pub provider_quote_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_lp_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = provider,
+        seeds = [b"lp_position", pool.key().as_ref(), provider.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", user.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = token_vault,
+        has_one = quote_vault,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    #[account(address = pool.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = token_mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = pool.quote_mint,
+        token::authority = user
+    )]
+    pub user_quote_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CollectPoolFees<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = token_vault,
+        has_one = quote_vault,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = pool.token_mint,
+        constraint = destination_token_account.owner != pool.key() @ ErrorCode::InvalidTokenAccount
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = pool.quote_mint
+    )]
+    pub destination_quote_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), destination_token_account.owner.as_ref()],
+        bump
+    )]
+    pub destination_investor_record: Box<Account<'info, InvestorRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLpIncome<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        has_one = property,
+        has_one = lp_mint,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_lp_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = provider,
+        seeds = [b"lp_position", pool.key().as_ref(), provider.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        mut,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump = income_vault.bump
+    )]
+    pub income_vault: Box<Account<'info, IncomeVault>>,
+    // Token accounts are only required when the property has an income mint
+    #[account(
+        mut,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub provider_income_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetOfferingRules<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct SetLockupSchedule<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct AddToWhitelist<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    /// CHECK: Investor being granted early access
+    pub investor: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 1,
+        seeds = [b"whitelist", property.key().as_ref(), investor.key().as_ref()],
+        bump
+    )]
+    pub whitelist_entry: Box<Account<'info, WhitelistEntry>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromWhitelist<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        mut,
+        has_one = property,
+        close = authority
+    )]
+    pub whitelist_entry: Box<Account<'info, WhitelistEntry>>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct CreateFundingRound<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 33 + 1 + 16 + 1,
+        seeds = [b"funding_round", property.key().as_ref(), &round_id.to_le_bytes()],
+        bump
+    )]
+    pub funding_round: Box<Account<'info, FundingRound>>,
+    // Round vault is only required when the property has a payment mint
+    #[account(
+        constraint = Some(payment_mint.key()) == property.payment_mint @ ErrorCode::InvalidPaymentMint
+    )]
+    pub payment_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"funding_vault", funding_round.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = round_vault
+    )]
+    pub round_vault: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeToRound<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut, has_one = property)]
+    pub funding_round: Box<Account<'info, FundingRound>>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", contributor.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"contribution", funding_round.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Box<Account<'info, Contribution>>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Box<Account<'info, InvestorRecord>>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        seeds = [b"price_history", platform_state.key().as_ref()],
+        bump = sol_price_history.bump
+    )]
+    pub sol_price_history: Option<Box<Account<'info, PriceHistory>>>,
+    // Only required during the early-access window
+    #[account(
+        seeds = [b"whitelist", property.key().as_ref(), contributor.key().as_ref()],
+        bump = whitelist_entry.bump
+    )]
+    pub whitelist_entry: Option<Box<Account<'info, WhitelistEntry>>>,
+    // Payment accounts are only required when the round has a payment mint
+    #[account(
+        mut,
+        token::authority = contributor
+    )]
+    pub contributor_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"funding_vault", funding_round.key().as_ref()],
+        bump
+    )]
+    pub round_vault: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeFundingRound<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut, has_one = property)]
+    pub funding_round: Box<Account<'info, FundingRound>>,
+    #[account(
+        mut,
+        seeds = [b"vault", property.key().as_ref()],
+        bump
+    )]
+    pub property_vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"funding_vault", funding_round.key().as_ref()],
+        bump
+    )]
+    pub round_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"payment_vault", property.key().as_ref()],
+        bump
+    )]
+    pub payment_vault: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRoundTokens<'info> {
+    pub property: Box<Account<'info, Property>>,
+    #[account(has_one = property)]
+    pub funding_round: Box<Account<'info, FundingRound>>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        mut,
+        has_one = funding_round,
+        has_one = contributor,
+        close = contributor
+    )]
+    pub contribution: Box<Account<'info, Contribution>>,
+    #[account(mut, address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = token_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Box<Account<'info, InvestorRecord>>,
+    /// CHECK: PDA mint authority, signs via seeds
+    #[account(
+        seeds = [b"mint_authority", property.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundContribution<'info> {
+    #[account(mut)]
+    pub funding_round: Box<Account<'info, FundingRound>>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        mut,
+        has_one = funding_round,
+        has_one = contributor,
+        close = contributor
+    )]
+    pub contribution: Box<Account<'info, Contribution>>,
+    #[account(
+        mut,
+        seeds = [b"funding_vault", funding_round.key().as_ref()],
+        bump
+    )]
+    pub round_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = contributor
+    )]
+    pub contributor_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(offering_id: u64)]
+pub struct CreateDutchOffering<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Box<Account<'info, PlatformState>>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1,
+        seeds = [b"dutch_offering", property.key().as_ref(), &offering_id.to_le_bytes()],
+        bump
+    )]
+    pub offering: Box<Account<'info, DutchOffering>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyDutchOffering<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut, has_one = property)]
+    pub offering: Box<Account<'info, DutchOffering>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        seeds = [b"kyc", buyer.key().as_ref()],
+        bump
+    )]
+    pub kyc_record: Box<Account<'info, KycRecord>>,
+    #[account(mut, address = property.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = token_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: PDA mint authority, signs via seeds
+    #[account(
+        seeds = [b"mint_authority", property.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", property.key().as_ref()],
+        bump
+    )]
+    pub property_vault: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = InvestorRecord::SPACE,
+        seeds = [b"investor", property.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub investor_record: Box<Account<'info, InvestorRecord>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"dutch_purchase", offering.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase: Box<Account<'info, DutchOfferingPurchase>>,
+    // Only required during the early-access window
+    #[account(
+        seeds = [b"whitelist", property.key().as_ref(), buyer.key().as_ref()],
+        bump = whitelist_entry.bump
+    )]
+    pub whitelist_entry: Option<Box<Account<'info, WhitelistEntry>>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDutchOffering<'info> {
+    #[account(mut)]
+    pub property: Box<Account<'info, Property>>,
+    #[account(mut, has_one = property)]
+    pub offering: Box<Account<'info, DutchOffering>>,
+    #[account(
+        mut,
+        seeds = [b"vault", property.key().as_ref()],
+        bump
+    )]
+    pub property_vault: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDutchRefund<'info> {
+    #[account(mut)]
+    pub offering: Box<Account<'info, DutchOffering>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        mut,
+        has_one = offering,
+        has_one = buyer,
+        close = buyer
+    )]
+    pub purchase: Box<Account<'info, DutchOfferingPurchase>>,
+}
+
+#[derive(Accounts)]
+pub struct InitiatePropertySale<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePropertySale<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSolPrice<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        seeds = [b"oracle_config", platform_state.key().as_ref()],
+        bump = oracle_config.bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+    #[account(
+        mut,
+        seeds = [b"price_history", platform_state.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+    // Use remaining_accounts for the oracle feeds listed in oracle_config
+}
+
+#[derive(Accounts)]
+pub struct InitializePriceHistory<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    /// CHECK: Price series subject, either the platform state or a property
+    pub subject: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = PriceHistory::SPACE,
+        seeds = [b"price_history", subject.key().as_ref()],
+        bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTwapWindow<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub price_history: Box<Account<'info, PriceHistory>>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureOracle<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigurePriceSources<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    /// CHECK: Price series subject, either the platform state or a property
+    pub subject: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = OracleConfig::SPACE,
+        seeds = [b"oracle_config", subject.key().as_ref()],
+        bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceStalenessWindow<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+// Batch operation contexts
+#[derive(Accounts)]
+#[instruction(total_income: u64, chainlink_round_id: u64)]
+pub struct BatchDistributeRentalIncome<'info> {
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"income_distribution", property.key().as_ref(), &chainlink_round_id.to_le_bytes()],
+        bump
+    )]
+    pub distribution: Box<Account<'info, IncomeDistribution>>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, PlatformTreasury>,
+    #[account(
+        mut,
+        seeds = [b"income_vault", property.key().as_ref()],
+        bump = income_vault.bump
+    )]
+    pub income_vault: Account<'info, IncomeVault>,
+    // Token accounts are only required when the property has an income mint
+    #[account(
+        mut,
+        token::authority = authority
+    )]
+    pub distributor_income_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"income_token_vault", property.key().as_ref()],
+        bump
+    )]
+    pub income_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = treasury
+    )]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    // Use remaining_accounts for dynamic number of investor records and payout destinations
+    // remaining_accounts: [investor_record_1, destination_1, investor_record_2, destination_2, ...]
 }
 
-#[account]
-pub struct InvestorRecord {
+#[derive(Accounts)]
+pub struct BatchTransferTokens<'info> {
+    pub property: Account<'info, Property>,
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    pub from_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"investor", property.key().as_ref(), from.key().as_ref()],
+        bump
+    )]
+    pub from_investor_record: Account<'info, InvestorRecord>,
+    pub token_program: Program<'info, Token>,
+    // Use remaining_accounts for dynamic number of recipient token accounts and investor records
+    // remaining_accounts: [to_token_account_1, to_investor_record_1, to_token_account_2, ...]
+}
+
+#[derive(Accounts)]
+pub struct BatchUpdateKycStatus<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub platform_state: Account<'info, PlatformState>,
+    pub system_program: Program<'info, System>,
+    // Use remaining_accounts for dynamic number of KYC records
+    // remaining_accounts: [kyc_record_1, kyc_record_2, ...]
+}
+
+#[derive(Accounts)]
+pub struct BatchClaimRentalIncome<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    // Use remaining_accounts for dynamic number of properties, investor records, and vaults
+    // remaining_accounts: [property_1, investor_record_1, income_vault_1, property_2, investor_record_2, income_vault_2, ...]
+    // Pattern: groups of 3 accounts per property (property, investor_record, income_vault),
+    // plus (income_token_vault, investor_income_account) for properties with an income mint
+}
+
+// Events
+#[event]
+pub struct PlatformInitialized {
+    pub authority: Pubkey,
+    pub platform_fee: u64,
+    pub governance_threshold: u64,
+}
+
+#[event]
+pub struct PropertyInitialized {
+    pub property_id: String,
+    pub owner: Pubkey,
+    pub total_tokens: u64,
+    pub token_price: u64,
+    pub token_mint: Pubkey,
+}
+
+#[event]
+pub struct PropertyValuationUpdated {
+    pub property_id: String,
+    pub old_valuation: u64,
+    pub new_valuation: u64,
+    pub chainlink_round_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenPriceUpdated {
+    pub property_id: String,
+    pub old_token_price: u64,
+    pub new_token_price: u64,
+    pub price_in_usd: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentVaultWithdrawn {
+    pub property_id: String,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PaymentMintSet {
+    pub property_id: String,
+    pub payment_mint: Pubkey,
+    pub payment_vault: Pubkey,
+}
+
+#[event]
+pub struct TokensPurchased {
+    pub property_id: String,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub total_cost: u64,
+    pub tokens_remaining: u64,
+}
+
+#[event]
+pub struct RentalIncomeDistributed {
+    pub property_id: String,
+    pub total_income: u64,
+    pub platform_fee: u64,
+    pub distributable_income: u64,
+    pub chainlink_round_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RentalIncomeClaimed {
+    pub property_id: String,
     pub investor: Pubkey,
-    pub property: Pubkey,
-    pub tokens_owned: u64,
-    pub total_invested: u64,
+    pub amount: u64,
     pub total_claimed: u64,
-    pub last_claim_time: i64,
 }
 
-#[account]
-pub struct Proposal {
-    pub property: Pubkey,
+#[event]
+pub struct ProposalCreated {
+    pub property_id: String,
     pub proposer: Pubkey,
-    pub title: String,              // 50 max
-    pub description: String,        // 200 max
+    pub title: String,
     pub proposal_type: ProposalType,
-    pub votes_for: u64,
-    pub votes_against: u64,
-    pub total_votes: u64,
-    pub created_at: i64,
     pub voting_ends_at: i64,
-    pub executed: bool,
-    pub passed: bool,
 }
 
-#[account]
-pub struct VoteRecord {
-    pub voter: Pubkey,
+#[event]
+pub struct VoteCast {
     pub proposal: Pubkey,
+    pub voter: Pubkey,
     pub vote_for: bool,
     pub voting_power: u64,
-    pub has_voted: bool,
-    pub voted_at: i64,
 }
 
-#[account]
-pub struct KycRecord {
+#[event]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub passed: bool,
+    pub votes_for: u64,
+    pub votes_against: u64,
+}
+
+#[event]
+pub struct TokensTransferred {
+    pub property_id: String,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct KycStatusUpdated {
     pub user: Pubkey,
     pub is_verified: bool,
     pub updated_at: i64,
-    pub verification_provider: String,
-    pub round_id: u64,
 }
 
-#[account]
-pub struct MarketListing {
+#[event]
+pub struct RentalYieldUpdated {
+    pub property_id: String,
+    pub new_yield: u64,
+    pub chainlink_round_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensListedForSale {
+    pub property_id: String,
     pub seller: Pubkey,
-    pub property: Pubkey,
     pub amount: u64,
     pub price_per_token: u64,
-    pub total_price: u64,
-    pub is_active: bool,
-    pub created_at: i64,
     pub market_price_reference: u64,
+    pub expires_at: Option<i64>,
+    pub price_in_usd: bool,
 }
 
-// Enums
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum PropertyType {
-    Residential,
-    Commercial,
-    Industrial,
-    Mixed,
+#[event]
+pub struct TokensPurchasedFromMarket {
+    pub property_id: String,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub total_cost: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum ProposalType {
-    RenovationApproval,
-    TenantApproval,
-    PropertySale,
-    ManagementChange,
+#[event]
+pub struct ListingCancelled {
+    pub property_id: String,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub amount_returned: u64,
+    pub timestamp: i64,
 }
 
-// Data structures for batch operations
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct TokenTransfer {
-    pub recipient: Pubkey,
-    pub amount: u64,
+#[event]
+pub struct ListingExpired {
+    pub property_id: String,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub amount_returned: u64,
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct KycUpdate {
-    pub user: Pubkey,
-    pub is_verified: bool,
-    pub chainlink_round_id: u64,
+#[event]
+pub struct ListingUpdated {
+    pub property_id: String,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub old_amount: u64,
+    pub new_amount: u64,
+    pub old_price_per_token: u64,
+    pub new_price_per_token: u64,
+    pub timestamp: i64,
 }
 
-// Context structures
-#[derive(Accounts)]
-pub struct InitializePlatform<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8,
-        seeds = [b"platform"],
-        bump
-    )]
-    pub platform_state: Account<'info, PlatformState>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct BidPlaced {
+    pub property_id: String,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub price_per_token: u64,
+    pub total_escrowed: u64,
 }
 
-#[derive(Accounts)]
-pub struct InitializeProperty<'info> {
-    #[account(
-        init,
-        payer = property_owner,
-        space = 8 + 4 + 32 + 32 + 8 + 8 + 8 + 4 + 100 + 1 + 4 + 32 + 8 + 8 + 1 + 32 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8
-    )]
-    pub property: Account<'info, Property>,
-    #[account(
-        init,
-        payer = property_owner,
-        mint::decimals = 0,
-        mint::authority = property_owner
-    )]
-    pub token_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub property_owner: Signer<'info>,
-    #[account(mut)]
-    pub platform_state: Account<'info, PlatformState>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+#[event]
+pub struct BidFilled {
+    pub property_id: String,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub total_cost: u64,
+    pub amount_remaining: u64,
 }
 
-#[derive(Accounts)]
-pub struct UpdatePropertyValuation<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct BidCancelled {
+    pub property_id: String,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub amount_cancelled: u64,
+    pub amount_refunded: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct PurchaseTokens<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    #[account(
-        seeds = [b"kyc", buyer.key().as_ref()],
-        bump
-    )]
-    pub kyc_record: Account<'info, KycRecord>,
-    #[account(mut)]
-    pub token_mint: Account<'info, Mint>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = buyer
-    )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        seeds = [b"vault", property.key().as_ref()],
-        bump
-    )]
-    pub property_vault: SystemAccount<'info>,
-    #[account(
-        init_if_needed,
-        payer = buyer,
-        space = 8 + 32 + 32 + 8 + 8 + 8 + 8,
-        seeds = [b"investor", property.key().as_ref(), buyer.key().as_ref()],
-        bump
-    )]
-    pub investor_record: Account<'info, InvestorRecord>,
-    /// CHECK: Property owner authority for token minting
-    pub property_owner: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct OrderBookInitialized {
+    pub property_id: String,
+    pub order_book: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct DistributeRentalIncome<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct OrderPlaced {
+    pub property_id: String,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub price: u64,
+    pub amount: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct ClaimRentalIncome<'info> {
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub investor: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [b"investor", property.key().as_ref(), investor.key().as_ref()],
-        bump
-    )]
-    pub investor_record: Account<'info, InvestorRecord>,
-    #[account(
-        mut,
-        seeds = [b"vault", property.key().as_ref()],
-        bump
-    )]
-    pub property_vault: SystemAccount<'info>,
+#[event]
+pub struct OrderCancelled {
+    pub property_id: String,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub amount_cancelled: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct CreateProposal<'info> {
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub proposer: Signer<'info>,
-    #[account(
-        seeds = [b"investor", property.key().as_ref(), proposer.key().as_ref()],
-        bump
-    )]
-    pub investor_record: Account<'info, InvestorRecord>,
-    #[account(
-        init,
-        payer = proposer,
-        space = 8 + 32 + 32 + 4 + 50 + 4 + 200 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 1
-    )]
-    pub proposal: Account<'info, Proposal>,
-    pub platform_state: Account<'info, PlatformState>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct OrdersMatched {
+    pub property_id: String,
+    pub bid_order_id: u64,
+    pub ask_order_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub amount: u64,
+    pub total_cost: u64,
 }
 
-#[derive(Accounts)]
-pub struct VoteOnProposal<'info> {
-    #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
-    #[account(mut)]
-    pub voter: Signer<'info>,
-    #[account(
-        seeds = [b"investor", proposal.property.as_ref(), voter.key().as_ref()],
-        bump
-    )]
-    pub investor_record: Account<'info, InvestorRecord>,
-    #[account(
-        init_if_needed,
-        payer = voter,
-        space = 8 + 32 + 32 + 1 + 8 + 1 + 8,
-        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
-        bump
-    )]
-    pub vote_record: Account<'info, VoteRecord>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct AuctionCreated {
+    pub property_id: String,
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub auction_type: AuctionType,
+    pub amount: u64,
+    pub start_price: u64,
+    pub reserve_price: u64,
+    pub end_time: i64,
 }
 
-#[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
-    #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
+#[event]
+pub struct AuctionBidPlaced {
+    pub property_id: String,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub price_per_token: u64,
+    pub total_bid: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub from: Signer<'info>,
-    /// CHECK: Safe as we only use it as a key
-    pub to: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub from_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub to_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        seeds = [b"investor", property.key().as_ref(), from.key().as_ref()],
-        bump
-    )]
-    pub from_investor_record: Account<'info, InvestorRecord>,
-    #[account(
-        init_if_needed,
-        payer = from,
-        space = 8 + 32 + 32 + 8 + 8 + 8 + 8,
-        seeds = [b"investor", property.key().as_ref(), to.key().as_ref()],
-        bump
-    )]
-    pub to_investor_record: Account<'info, InvestorRecord>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct AuctionSettled {
+    pub property_id: String,
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub winner: Option<Pubkey>,
+    pub amount: u64,
+    pub price_per_token: u64,
+    pub total_cost: u64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateKycStatus<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
-    #[account(
-        init_if_needed,
-        payer = authority,
-        space = 8 + 32 + 1 + 8 + 4 + 20 + 8 // Added space for verification_provider and round_id
-    )]
-    pub kyc_record: Account<'info, KycRecord>,
-    /// CHECK: User whose KYC status is being updated
-    pub user: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct PoolInitialized {
+    pub property_id: String,
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub fee_bps: u64,
+    pub platform_fee_share_bps: u64,
 }
 
-#[derive(Accounts)]
-pub struct VerifyUserKyc<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
-    #[account(
-        init_if_needed,
-        payer = authority,
-        space = 8 + 32 + 1 + 8 + 4 + 20 + 8
-    )]
-    pub kyc_record: Account<'info, KycRecord>,
-    /// CHECK: User whose KYC status is being verified
-    pub user: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    pub lp_amount: u64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateRentalYield<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    pub lp_amount: u64,
 }
 
-#[derive(Accounts)]
-pub struct ListTokensForSale<'info> {
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    #[account(
-        init,
-        payer = seller,
-        space = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 8
-    )]
-    pub market_listing: Account<'info, MarketListing>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct TokensSwapped {
+    pub property_id: String,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub direction: SwapDirection,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub platform_fee: u64,
 }
 
-#[derive(Accounts)]
-pub struct BuyFromMarket<'info> {
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    #[account(mut)]
-    pub market_listing: Account<'info, MarketListing>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct PoolFeesCollected {
+    pub pool: Pubkey,
+    pub token_amount: u64,
+    pub quote_amount: u64,
 }
 
-#[derive(Accounts)]
-pub struct InitiatePropertySale<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct TradingFeesCollected {
+    pub property_id: String,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub platform_fee: u64,
+    pub royalty: u64,
 }
 
-#[derive(Accounts)]
-pub struct ExecutePropertySale<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct TradingFeeUpdated {
+    pub old_trading_fee: u64,
+    pub new_trading_fee: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateSolPrice<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(mut)]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct PropertyRoyaltyUpdated {
+    pub property_id: String,
+    pub old_royalty_bps: u64,
+    pub new_royalty_bps: u64,
+    pub timestamp: i64,
 }
 
-// Batch operation contexts
-#[derive(Accounts)]
-pub struct BatchDistributeRentalIncome<'info> {
-    #[account(mut)]
-    pub property: Account<'info, Property>,
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
-    // Use remaining_accounts for dynamic number of investor records
-    // remaining_accounts: [investor_record_1, investor_record_2, ...]
+#[event]
+pub struct TreasuryWithdrawn {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct BatchTransferTokens<'info> {
-    pub property: Account<'info, Property>,
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    pub from_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        seeds = [b"investor", property.key().as_ref(), from.key().as_ref()],
-        bump
-    )]
-    pub from_investor_record: Account<'info, InvestorRecord>,
-    pub token_program: Program<'info, Token>,
-    // Use remaining_accounts for dynamic number of recipient token accounts
-    // remaining_accounts: [to_token_account_1, to_token_account_2, ...]
+#[event]
+pub struct AppraiserRegistered {
+    pub appraiser: Pubkey,
+    pub license_id: String,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct BatchUpdateKycStatus<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub platform_state: Account<'info, PlatformState>,
-    pub system_program: Program<'info, System>,
-    // Use remaining_accounts for dynamic number of KYC records
-    // remaining_accounts: [kyc_record_1, kyc_record_2, ...]
+#[event]
+pub struct AppraiserRevoked {
+    pub appraiser: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct BatchClaimRentalIncome<'info> {
-    #[account(mut)]
-    pub investor: Signer<'info>,
-    // Use remaining_accounts for dynamic number of properties, investor records, and vaults
-    // remaining_accounts: [property_1, investor_record_1, vault_1, property_2, investor_record_2, vault_2, ...]
-    // Pattern: groups of 3 accounts per property (property, investor_record, vault)
+#[event]
+pub struct PropertyAppraised {
+    pub property_id: String,
+    pub appraiser: Pubkey,
+    pub old_valuation: u64,
+    pub new_valuation: u64,
+    pub report_hash: [u8; 32],
+    pub effective_date: i64,
 }
 
-// Events
 #[event]
-pub struct PlatformInitialized {
-    pub authority: Pubkey,
-    pub platform_fee: u64,
-    pub governance_threshold: u64,
+pub struct OfferingRulesUpdated {
+    pub property_id: String,
+    pub min_purchase: u64,
+    pub max_purchase: u64,
+    pub max_ownership_bps: u64,
+    pub public_sale_starts_at: i64,
 }
 
 #[event]
-pub struct PropertyInitialized {
+pub struct LockupScheduleUpdated {
     pub property_id: String,
-    pub owner: Pubkey,
-    pub total_tokens: u64,
-    pub token_price: u64,
-    pub token_mint: Pubkey,
+    pub cliff: i64,
+    pub duration: i64,
 }
 
 #[event]
-pub struct PropertyValuationUpdated {
+pub struct WhitelistUpdated {
     pub property_id: String,
-    pub old_valuation: u64,
-    pub new_valuation: u64,
-    pub chainlink_round_id: u64,
-    pub timestamp: i64,
+    pub investor: Pubkey,
+    pub allocation: u64,
+    pub is_whitelisted: bool,
 }
 
 #[event]
-pub struct TokensPurchased {
+pub struct FundingRoundCreated {
     pub property_id: String,
-    pub buyer: Pubkey,
+    pub funding_round: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub soft_cap: u64,
+    pub hard_cap: u64,
+}
+
+#[event]
+pub struct RoundContributionMade {
+    pub property_id: String,
+    pub funding_round: Pubkey,
+    pub contributor: Pubkey,
     pub amount: u64,
     pub total_cost: u64,
-    pub tokens_remaining: u64,
+    pub tokens_committed: u64,
 }
 
 #[event]
-pub struct RentalIncomeDistributed {
+pub struct FundingRoundFinalized {
     pub property_id: String,
-    pub total_income: u64,
-    pub platform_fee: u64,
-    pub distributable_income: u64,
-    pub chainlink_round_id: u64,
-    pub timestamp: i64,
+    pub funding_round: Pubkey,
+    pub succeeded: bool,
+    pub tokens_committed: u64,
+    pub total_raised: u64,
 }
 
 #[event]
-pub struct RentalIncomeClaimed {
+pub struct RoundTokensClaimed {
     pub property_id: String,
-    pub investor: Pubkey,
+    pub funding_round: Pubkey,
+    pub contributor: Pubkey,
     pub amount: u64,
-    pub total_claimed: u64,
 }
 
 #[event]
-pub struct ProposalCreated {
-    pub property_id: String,
-    pub proposer: Pubkey,
-    pub title: String,
-    pub proposal_type: ProposalType,
-    pub voting_ends_at: i64,
+pub struct ContributionRefunded {
+    pub funding_round: Pubkey,
+    pub contributor: Pubkey,
+    pub amount_refunded: u64,
 }
 
 #[event]
-pub struct VoteCast {
-    pub proposal: Pubkey,
-    pub voter: Pubkey,
-    pub vote_for: bool,
-    pub voting_power: u64,
+pub struct DutchOfferingCreated {
+    pub property_id: String,
+    pub offering: Pubkey,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub tokens_offered: u64,
+    pub uniform_clearing: bool,
 }
 
 #[event]
-pub struct ProposalExecuted {
-    pub proposal: Pubkey,
-    pub passed: bool,
-    pub votes_for: u64,
-    pub votes_against: u64,
+pub struct DutchOfferingFinalized {
+    pub property_id: String,
+    pub offering: Pubkey,
+    pub clearing_price: u64,
+    pub tokens_sold: u64,
+    pub total_raised: u64,
 }
 
 #[event]
-pub struct TokensTransferred {
-    pub property_id: String,
-    pub from: Pubkey,
-    pub to: Pubkey,
-    pub amount: u64,
+pub struct DutchRefundClaimed {
+    pub offering: Pubkey,
+    pub buyer: Pubkey,
+    pub tokens: u64,
+    pub amount_refunded: u64,
 }
 
 #[event]
-pub struct KycStatusUpdated {
-    pub user: Pubkey,
-    pub is_verified: bool,
-    pub updated_at: i64,
+pub struct IncomeMintSet {
+    pub property_id: String,
+    pub income_mint: Pubkey,
+    pub income_token_vault: Pubkey,
 }
 
 #[event]
-pub struct RentalYieldUpdated {
+pub struct MerkleDistributionCreated {
     pub property_id: String,
-    pub new_yield: u64,
-    pub chainlink_round_id: u64,
-    pub timestamp: i64,
+    pub distribution_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_income: u64,
+    pub platform_fee: u64,
+    pub distributable_income: u64,
+    pub num_leaves: u32,
 }
 
 #[event]
-pub struct TokensListedForSale {
+pub struct MerkleIncomeClaimed {
     pub property_id: String,
-    pub seller: Pubkey,
+    pub distribution_id: u64,
+    pub investor: Pubkey,
+    pub index: u32,
     pub amount: u64,
-    pub price_per_token: u64,
-    pub market_price_reference: u64,
 }
 
 #[event]
-pub struct TokensPurchasedFromMarket {
+pub struct LpIncomeClaimed {
     pub property_id: String,
-    pub seller: Pubkey,
-    pub buyer: Pubkey,
+    pub pool: Pubkey,
+    pub provider: Pubkey,
     pub amount: u64,
-    pub total_cost: u64,
+    pub total_claimed: u64,
 }
 
 #[event]
@@ -1560,6 +8768,36 @@ pub struct SolPriceUpdated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct OracleConfigured {
+    pub oracle_program: Pubkey,
+    pub max_confidence_bps: u64,
+}
+
+#[event]
+pub struct TwapComputed {
+    pub subject: Pubkey,
+    pub window: i64,
+    pub twap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceSourcesConfigured {
+    pub subject: Pubkey,
+    pub sources: Vec<Pubkey>,
+    pub min_sources: u8,
+    pub max_deviation_bps: u64,
+}
+
+#[event]
+pub struct OracleDeviationOverridden {
+    pub subject: Pubkey,
+    pub old_value: u64,
+    pub new_value: u64,
+    pub authority: Pubkey,
+}
+
 // Batch operation events
 #[event]
 pub struct BatchRentalIncomeDistributed {
@@ -1682,4 +8920,723 @@ pub enum ErrorCode {
     InvalidPropertyKey,
     #[msg("Invalid accounts length")]
     InvalidAccountsLength,
+    #[msg("Cannot buy from own listing")]
+    CannotBuyOwnListing,
+    #[msg("Invalid listing expiry")]
+    InvalidListingExpiry,
+    #[msg("Listing expired")]
+    ListingExpired,
+    #[msg("Listing not expired")]
+    ListingNotExpired,
+    #[msg("Bid not active")]
+    BidNotActive,
+    #[msg("Cannot fill own bid")]
+    CannotFillOwnBid,
+    #[msg("Order book full")]
+    OrderBookFull,
+    #[msg("Order not found")]
+    OrderNotFound,
+    #[msg("Order would trade against own order")]
+    SelfTrade,
+    #[msg("No matching orders")]
+    NoMatchingOrders,
+    #[msg("Invalid order owner")]
+    InvalidOrderOwner,
+    #[msg("Missing token account")]
+    MissingTokenAccount,
+    #[msg("Invalid auction duration")]
+    InvalidAuctionDuration,
+    #[msg("Invalid auction type")]
+    InvalidAuctionType,
+    #[msg("Auction already settled")]
+    AuctionSettled,
+    #[msg("Auction ended")]
+    AuctionEnded,
+    #[msg("Auction not ended")]
+    AuctionNotEnded,
+    #[msg("Bid too low")]
+    BidTooLow,
+    #[msg("Invalid previous bidder")]
+    InvalidPreviousBidder,
+    #[msg("Invalid auction winner")]
+    InvalidAuctionWinner,
+    #[msg("Invalid fee")]
+    InvalidFee,
+    #[msg("Insufficient liquidity")]
+    InsufficientLiquidity,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Token sale already started")]
+    SaleAlreadyStarted,
+    #[msg("Invalid payment mint")]
+    InvalidPaymentMint,
+    #[msg("SOL/USD price is stale")]
+    StalePrice,
+    #[msg("Invalid staleness window")]
+    InvalidStalenessWindow,
+    #[msg("Invalid oracle feed")]
+    InvalidOracleFeed,
+    #[msg("Oracle confidence interval too wide")]
+    OracleConfidenceTooLow,
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    #[msg("Not enough fresh oracle sources")]
+    InsufficientOracleSources,
+    #[msg("Price deviation exceeds threshold")]
+    PriceDeviationTooLarge,
+    #[msg("No price history recorded")]
+    NoPriceHistory,
+    #[msg("Price history account required")]
+    MissingPriceHistory,
+    #[msg("Invalid TWAP window")]
+    InvalidTwapWindow,
+    #[msg("License ID too long")]
+    LicenseIdTooLong,
+    #[msg("Appraiser is not active")]
+    AppraiserNotActive,
+    #[msg("Appraisal must be newer than the current one and not future-dated")]
+    InvalidAppraisalDate,
+    #[msg("Missing or invalid Ed25519 attestation")]
+    InvalidAttestation,
+    #[msg("Invalid funding round parameters")]
+    InvalidFundingRound,
+    #[msg("A funding round is in progress")]
+    FundingRoundInProgress,
+    #[msg("Funding round is not open")]
+    FundingRoundNotOpen,
+    #[msg("Funding round hard cap exceeded")]
+    HardCapExceeded,
+    #[msg("Funding round has not ended")]
+    FundingRoundNotEnded,
+    #[msg("Funding round already finalized")]
+    FundingRoundFinalized,
+    #[msg("Funding round did not succeed")]
+    FundingRoundNotSucceeded,
+    #[msg("Funding round did not fail")]
+    FundingRoundNotFailed,
+    #[msg("Invalid offering rules")]
+    InvalidOfferingRules,
+    #[msg("Purchase below minimum ticket size")]
+    BelowMinimumPurchase,
+    #[msg("Purchase exceeds investor limit")]
+    PurchaseLimitExceeded,
+    #[msg("Purchase exceeds maximum ownership share")]
+    OwnershipLimitExceeded,
+    #[msg("Investor not whitelisted for early access")]
+    NotWhitelisted,
+    #[msg("Invalid lock-up schedule")]
+    InvalidLockupSchedule,
+    #[msg("Tokens are still locked")]
+    TokensLocked,
+    #[msg("Offering not finalized")]
+    OfferingNotFinalized,
+    #[msg("Invalid income vault")]
+    InvalidIncomeVault,
+    #[msg("Invalid income mint")]
+    InvalidIncomeMint,
+    #[msg("Distribution round already funded")]
+    DistributionAlreadyFunded,
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Already claimed")]
+    AlreadyClaimed,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Order value below the minimum")]
+    OrderTooSmall,
+    #[msg("Too many open orders for this owner")]
+    TooManyOpenOrders,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        anchor_lang::solana_program::keccak::hashv(&[&[1u8], &left, &right]).to_bytes()
+    }
+
+    fn investor_record(tokens_owned: u64) -> InvestorRecord {
+        InvestorRecord {
+            investor: Pubkey::new_unique(),
+            property: Pubkey::new_unique(),
+            tokens_owned,
+            total_invested: 0,
+            total_claimed: 0,
+            last_claim_time: 0,
+            lockups: [Lockup::default(); InvestorRecord::MAX_LOCKUPS],
+            income_checkpoint: 0,
+            pending_income: 0,
+            last_distribution_paid: 0,
+            total_invested_payment: 0,
+            tokens_escrowed: 0,
+        }
+    }
+
+    fn platform_state(oracle_program: Pubkey) -> PlatformState {
+        PlatformState {
+            authority: Pubkey::new_unique(),
+            platform_fee: 100,
+            governance_threshold: 1000,
+            total_properties: 0,
+            total_value_locked: 0,
+            sol_usd_price: 0,
+            last_price_update: 0,
+            trading_fee: 50,
+            price_staleness_window: PlatformState::DEFAULT_PRICE_STALENESS_WINDOW,
+            oracle_program,
+            max_confidence_bps: PlatformState::DEFAULT_MAX_CONFIDENCE_BPS,
+            twap_window: 0,
+            value_locked_by_type: [0; PropertyType::COUNT],
+        }
+    }
+
+    fn feed_data(price: i64, confidence: u64, publish_time: i64, round_id: u64) -> Vec<u8> {
+        OracleFeed {
+            magic: OracleFeed::MAGIC,
+            price,
+            confidence,
+            exponent: -8,
+            round_id,
+            publish_time,
+        }
+        .try_to_vec()
+        .unwrap()
+    }
+
+    fn order(order_id: u64, owner: Pubkey, price: u64, amount: u64) -> Order {
+        Order {
+            order_id,
+            owner,
+            price,
+            amount,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn order_book_keeps_price_time_priority() {
+        let mut book = OrderBook {
+            property: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            next_order_id: 0,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            bump: 0,
+        };
+        let price = OrderBook::MIN_ORDER_NOTIONAL;
+        book.insert_order(&OrderSide::Bid, order(0, Pubkey::new_unique(), price, 1)).unwrap();
+        book.insert_order(&OrderSide::Bid, order(1, Pubkey::new_unique(), price * 2, 1)).unwrap();
+        book.insert_order(&OrderSide::Bid, order(2, Pubkey::new_unique(), price, 1)).unwrap();
+        book.insert_order(&OrderSide::Ask, order(3, Pubkey::new_unique(), price * 2, 1)).unwrap();
+        book.insert_order(&OrderSide::Ask, order(4, Pubkey::new_unique(), price, 1)).unwrap();
+
+        let bid_ids: Vec<u64> = book.bids.iter().map(|o| o.order_id).collect();
+        let ask_ids: Vec<u64> = book.asks.iter().map(|o| o.order_id).collect();
+        assert_eq!(bid_ids, vec![1, 0, 2]);
+        assert_eq!(ask_ids, vec![4, 3]);
+    }
+
+    #[test]
+    fn order_book_rejects_dust_and_caps_orders_per_owner() {
+        let mut book = OrderBook {
+            property: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            next_order_id: 0,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            bump: 0,
+        };
+        let owner = Pubkey::new_unique();
+        assert_eq!(
+            book.insert_order(&OrderSide::Ask, order(0, owner, OrderBook::MIN_ORDER_NOTIONAL - 1, 1))
+                .unwrap_err(),
+            error!(ErrorCode::OrderTooSmall)
+        );
+
+        for id in 0..OrderBook::MAX_ORDERS_PER_OWNER as u64 {
+            book.insert_order(&OrderSide::Ask, order(id, owner, OrderBook::MIN_ORDER_NOTIONAL, 1))
+                .unwrap();
+        }
+        assert_eq!(
+            book.insert_order(&OrderSide::Ask, order(99, owner, OrderBook::MIN_ORDER_NOTIONAL, 1))
+                .unwrap_err(),
+            error!(ErrorCode::TooManyOpenOrders)
+        );
+
+        // The cap is per side and per owner
+        book.insert_order(&OrderSide::Bid, order(100, owner, OrderBook::MIN_ORDER_NOTIONAL, 1))
+            .unwrap();
+        book.insert_order(
+            &OrderSide::Ask,
+            order(101, Pubkey::new_unique(), OrderBook::MIN_ORDER_NOTIONAL, 1),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn integer_sqrt_floors() {
+        for value in 0..1000u128 {
+            let root = integer_sqrt(value);
+            assert!(root * root <= value);
+            assert!((root + 1) * (root + 1) > value);
+        }
+        assert_eq!(integer_sqrt(u64::MAX as u128 * u64::MAX as u128), u64::MAX as u128);
+        assert_eq!(integer_sqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn lp_income_follows_lp_balances() {
+        let precision = Property::INCOME_PRECISION;
+        let mut pool = LiquidityPool {
+            property: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            fee_bps: 30,
+            platform_fee_share_bps: 0,
+            platform_fees_token: 0,
+            platform_fees_quote: 0,
+            income_per_lp_share: 0,
+            income_indexed: 0,
+            bump: 0,
+        };
+        let position = |provider| LpPosition {
+            pool: Pubkey::default(),
+            provider,
+            income_checkpoint: 0,
+            pending_income: 0,
+            total_claimed: 0,
+            bump: 0,
+        };
+        let mut alice = position(Pubkey::new_unique());
+        let mut bob = position(Pubkey::new_unique());
+        let mut pool_record = investor_record(1000);
+
+        // Alice holds every LP share while the pool earns 3 per token
+        let lp_supply = LiquidityPool::MINIMUM_LIQUIDITY + 3000;
+        pool_record.settle_income(3 * precision).unwrap();
+        pool.accrue_lp_income(&pool_record, lp_supply).unwrap();
+        alice.settle_income(pool.income_per_lp_share, 3000).unwrap();
+        assert_eq!(alice.pending_income, 3000);
+
+        // Bob matches Alice's deposit and only earns from then on
+        bob.settle_income(pool.income_per_lp_share, 0).unwrap();
+        let lp_supply = lp_supply + 3000;
+        pool_record.settle_income(6 * precision).unwrap();
+        pool.accrue_lp_income(&pool_record, lp_supply).unwrap();
+        alice.settle_income(pool.income_per_lp_share, 3000).unwrap();
+        bob.settle_income(pool.income_per_lp_share, 3000).unwrap();
+        assert_eq!(alice.pending_income, 3000 + 1500);
+        assert_eq!(bob.pending_income, 1500);
+        assert_eq!(pool.income_indexed, pool_record.pending_income);
+
+        // Accruing again without new income changes nothing
+        let index = pool.income_per_lp_share;
+        pool.accrue_lp_income(&pool_record, lp_supply).unwrap();
+        assert_eq!(pool.income_per_lp_share, index);
+    }
+
+    #[test]
+    fn lp_income_waits_for_providers() {
+        let mut pool = LiquidityPool {
+            property: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            fee_bps: 30,
+            platform_fee_share_bps: 0,
+            platform_fees_token: 0,
+            platform_fees_quote: 0,
+            income_per_lp_share: 0,
+            income_indexed: 0,
+            bump: 0,
+        };
+        let mut pool_record = investor_record(10);
+        pool_record.settle_income(Property::INCOME_PRECISION).unwrap();
+
+        // Only the locked minimum exists: nothing is indexed yet
+        pool.accrue_lp_income(&pool_record, LiquidityPool::MINIMUM_LIQUIDITY).unwrap();
+        assert_eq!(pool.income_per_lp_share, 0);
+        assert_eq!(pool.income_indexed, 0);
+
+        pool.accrue_lp_income(&pool_record, LiquidityPool::MINIMUM_LIQUIDITY + 10).unwrap();
+        assert_eq!(pool.income_per_lp_share, Property::INCOME_PRECISION);
+        assert_eq!(pool.income_indexed, 10);
+    }
+
+    #[test]
+    fn trade_proceeds_split_sums_to_total() {
+        let proceeds = TradeProceeds::split(1_000_000, 250, 100).unwrap();
+        assert_eq!(proceeds.platform_fee, 25_000);
+        assert_eq!(proceeds.royalty, 10_000);
+        assert_eq!(proceeds.seller_proceeds, 965_000);
+
+        // Fees round down in the seller's favour
+        let proceeds = TradeProceeds::split(999, 250, 100).unwrap();
+        assert_eq!(proceeds.platform_fee, 24);
+        assert_eq!(proceeds.royalty, 9);
+        assert_eq!(
+            proceeds.platform_fee + proceeds.royalty + proceeds.seller_proceeds,
+            999
+        );
+
+        assert!(TradeProceeds::split(u64::MAX, 2, 0).is_err());
+        assert!(TradeProceeds::split(1000, 6000, 5000).is_err());
+    }
+
+    #[test]
+    fn oracle_deviation_threshold() {
+        let config = OracleConfig {
+            subject: Pubkey::new_unique(),
+            sources: Vec::new(),
+            min_sources: 1,
+            max_deviation_bps: 500,
+            bump: 0,
+        };
+        assert!(config.within_deviation(0, u64::MAX).unwrap());
+        assert!(config.within_deviation(10_000, 10_500).unwrap());
+        assert!(config.within_deviation(10_000, 9_500).unwrap());
+        assert!(!config.within_deviation(10_000, 10_501).unwrap());
+        assert!(!config.within_deviation(10_000, 9_499).unwrap());
+    }
+
+    #[test]
+    fn oracle_aggregate_takes_median_of_valid_feeds() {
+        let oracle_program = Pubkey::new_unique();
+        let platform = platform_state(oracle_program);
+        let now = 10_000;
+
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let mut lamports = [0u64; 5];
+        let mut data = [
+            feed_data(100_00000000, 0, now, 1),
+            feed_data(102_00000000, 0, now - 10, 4),
+            feed_data(101_00000000, 0, now - 5, 2),
+            // Stale: skipped
+            feed_data(500_00000000, 0, now - platform.price_staleness_window - 1, 9),
+            // Confidence of 10% is above the 2% limit: skipped
+            feed_data(1_00000000, 10000000, now, 9),
+        ];
+        let feeds: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, false, lamports, data, &oracle_program, false, 0)
+            })
+            .collect();
+
+        let mut config = OracleConfig {
+            subject: Pubkey::new_unique(),
+            sources: keys.clone(),
+            min_sources: 3,
+            max_deviation_bps: 500,
+            bump: 0,
+        };
+        let reading = config.aggregate(&feeds, &platform, now).unwrap();
+        assert_eq!(reading.value, 101_00000000);
+        assert_eq!(reading.round_id, 4);
+        assert_eq!(reading.publish_time, now - 10);
+
+        let reading = config.aggregate(&feeds[..2], &platform, now);
+        assert_eq!(reading.err(), Some(error!(ErrorCode::InsufficientOracleSources)));
+
+        // Even count averages the middle pair
+        config.min_sources = 2;
+        let reading = config.aggregate(&feeds[..2], &platform, now).unwrap();
+        assert_eq!(reading.value, 101_00000000);
+
+        // Too few usable feeds
+        config.min_sources = 4;
+        assert_eq!(
+            config.aggregate(&feeds, &platform, now).err(),
+            Some(error!(ErrorCode::InsufficientOracleSources))
+        );
+
+        // Unknown source and duplicated source are rejected
+        config.min_sources = 1;
+        config.sources = keys[1..].to_vec();
+        assert_eq!(
+            config.aggregate(&feeds, &platform, now).err(),
+            Some(error!(ErrorCode::InvalidOracleFeed))
+        );
+        config.sources = keys.clone();
+        let duplicated = [feeds[0].clone(), feeds[0].clone()];
+        assert_eq!(
+            config.aggregate(&duplicated, &platform, now).err(),
+            Some(error!(ErrorCode::InvalidOracleFeed))
+        );
+    }
+
+    #[test]
+    fn oracle_aggregate_rejects_feeds_from_other_programs() {
+        let platform = platform_state(Pubkey::new_unique());
+        let key = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = feed_data(100_00000000, 0, 0, 1);
+        let feed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &other_program, false, 0);
+        let config = OracleConfig {
+            subject: Pubkey::new_unique(),
+            sources: vec![key],
+            min_sources: 1,
+            max_deviation_bps: 500,
+            bump: 0,
+        };
+        assert_eq!(
+            config.aggregate(&[feed], &platform, 0).err(),
+            Some(error!(ErrorCode::InvalidOracleFeed))
+        );
+    }
+
+    #[test]
+    fn twap_weights_observations_by_duration() {
+        let mut history = PriceHistory {
+            subject: Pubkey::new_unique(),
+            head: 0,
+            observations: Vec::new(),
+            bump: 0,
+        };
+        assert_eq!(
+            history.twap(100, 60).unwrap_err(),
+            error!(ErrorCode::NoPriceHistory)
+        );
+
+        history.record(0, 100, 1);
+        history.record(30, 200, 2);
+        // 100 for 30s, then 200 for 30s
+        assert_eq!(history.twap(60, 60).unwrap(), 150);
+        // Window clipped to [45, 60]: only the 200 segment counts
+        assert_eq!(history.twap(60, 15).unwrap(), 200);
+        // History shorter than the window averages what is recorded
+        assert_eq!(history.twap(60, 3600).unwrap(), 150);
+        // Zero-length window falls back to the latest value
+        assert_eq!(history.twap(30, 0).unwrap(), 200);
+    }
+
+    #[test]
+    fn twap_follows_the_ring_buffer_after_wraparound() {
+        let mut history = PriceHistory {
+            subject: Pubkey::new_unique(),
+            head: 0,
+            observations: Vec::new(),
+            bump: 0,
+        };
+        let total = PriceHistory::CAPACITY as i64 + 3;
+        for i in 0..total {
+            history.record(i * 10, i as u64, i as u64);
+        }
+        assert_eq!(history.observations.len(), PriceHistory::CAPACITY);
+        assert_eq!(history.head, 3);
+        assert_eq!(
+            history.chronological().next().unwrap().timestamp,
+            3 * 10
+        );
+        assert_eq!(
+            history.chronological().last().unwrap().value,
+            (total - 1) as u64
+        );
+
+        // Last two observations, 10s each
+        let now = total * 10;
+        assert_eq!(history.twap(now, 20).unwrap(), (2 * total as u64 - 3) / 2);
+    }
+
+    #[test]
+    fn lockup_holds_until_cliff_then_vests_linearly() {
+        let lockup = Lockup {
+            amount: 1000,
+            start: 0,
+            cliff: 100,
+            end: 400,
+        };
+        assert_eq!(lockup.locked(-1), 1000);
+        assert_eq!(lockup.locked(99), 1000);
+        assert_eq!(lockup.locked(100), 750);
+        assert_eq!(lockup.locked(200), 500);
+        assert_eq!(lockup.locked(399), 3);
+        assert_eq!(lockup.locked(400), 0);
+        assert_eq!(Lockup::default().locked(0), 0);
+    }
+
+    #[test]
+    fn locked_tokens_sums_every_tranche() {
+        let mut record = investor_record(3000);
+        record.add_lockup(1000, 0, 100, 400).unwrap();
+        record.add_lockup(2000, 200, 0, 200).unwrap();
+
+        assert_eq!(record.locked_tokens(50), 1000 + 2000);
+        assert_eq!(record.locked_tokens(200), 500 + 2000);
+        assert_eq!(record.locked_tokens(300), 250 + 1000);
+        assert_eq!(record.locked_tokens(400), 0);
+
+        // A zero-duration purchase is not locked at all
+        record.add_lockup(500, 0, 0, 0).unwrap();
+        assert_eq!(record.locked_tokens(50), 3000);
+    }
+
+    #[test]
+    fn add_lockup_merges_reuses_and_folds_slots() {
+        let mut record = investor_record(0);
+        record.add_lockup(100, 0, 10, 100).unwrap();
+        record.add_lockup(50, 0, 10, 100).unwrap();
+        assert_eq!(record.lockups[0].amount, 150);
+        assert_eq!(record.lockups[1].amount, 0);
+
+        // Later purchases do not re-lock tokens that were already vesting
+        record.add_lockup(100, 50, 10, 100).unwrap();
+        assert_eq!(record.lockups[0].locked(50), 75);
+        assert_eq!(record.locked_tokens(50), 75 + 100);
+
+        for i in 2..InvestorRecord::MAX_LOCKUPS as i64 {
+            record.add_lockup(1, 50 + i, 10, 100).unwrap();
+        }
+        let last = InvestorRecord::MAX_LOCKUPS - 1;
+        assert_eq!(record.lockups[last].end, 157);
+
+        // With every slot in use, a purchase folds into the slot ending last and takes over
+        // the later schedule, without unlocking anything early
+        let locked_before = record.locked_tokens(60);
+        record.add_lockup(1, 60, 10, 100).unwrap();
+        let slot = record.lockups[last];
+        assert_eq!((slot.amount, slot.start, slot.cliff, slot.end), (2, 60, 70, 160));
+        assert_eq!(record.locked_tokens(60), locked_before + 1);
+
+        // A shorter schedule keeps the slot's later cliff and end
+        let locked_before = record.locked_tokens(61);
+        record.add_lockup(5, 61, 0, 50).unwrap();
+        let slot = record.lockups[last];
+        assert_eq!((slot.amount, slot.start, slot.cliff, slot.end), (7, 61, 70, 160));
+        assert_eq!(record.locked_tokens(61), locked_before + 5);
+
+        // Once the first tranche has vested its slot is reused
+        record.add_lockup(1, 100, 10, 100).unwrap();
+        assert_eq!(record.lockups[0].start, 100);
+        assert_eq!(record.lockups[0].amount, 1);
+    }
+
+    #[test]
+    fn require_unlocked_accounts_for_lockups_and_escrow() {
+        let mut record = investor_record(1000);
+        record.add_lockup(400, 0, 100, 100).unwrap();
+
+        record.require_unlocked(600, 0).unwrap();
+        assert_eq!(
+            record.require_unlocked(601, 0).unwrap_err(),
+            error!(ErrorCode::TokensLocked)
+        );
+        assert_eq!(
+            record.require_unlocked(1001, 200).unwrap_err(),
+            error!(ErrorCode::InsufficientTokens)
+        );
+
+        // Escrowed tokens cannot be committed a second time
+        record.escrow_tokens(500, 0).unwrap();
+        record.require_unlocked(100, 0).unwrap();
+        assert_eq!(
+            record.require_unlocked(101, 0).unwrap_err(),
+            error!(ErrorCode::TokensLocked)
+        );
+        assert_eq!(
+            record.escrow_tokens(501, 200).unwrap_err(),
+            error!(ErrorCode::InsufficientTokens)
+        );
+        record.escrow_tokens(500, 200).unwrap();
+        assert_eq!(
+            record.require_unlocked(1, 200).unwrap_err(),
+            error!(ErrorCode::InsufficientTokens)
+        );
+
+        record.release_escrowed(1000).unwrap();
+        record.require_unlocked(1000, 200).unwrap();
+        assert_eq!(
+            record.release_escrowed(1).unwrap_err(),
+            error!(ErrorCode::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn settle_income_accrues_against_the_checkpoint() {
+        let precision = Property::INCOME_PRECISION;
+        let mut record = investor_record(200);
+
+        record.settle_income(3 * precision).unwrap();
+        assert_eq!(record.pending_income, 600);
+        assert_eq!(record.income_checkpoint, 3 * precision);
+
+        // Settling again at the same index accrues nothing
+        record.settle_income(3 * precision).unwrap();
+        assert_eq!(record.pending_income, 600);
+
+        // A changed holding only earns from its own checkpoint onwards
+        record.tokens_owned = 50;
+        record.settle_income(3 * precision + precision / 2).unwrap();
+        assert_eq!(record.pending_income, 625);
+
+        // An index below the checkpoint is an error, not a wrap-around
+        assert_eq!(
+            record.settle_income(precision).unwrap_err(),
+            error!(ErrorCode::MathOverflow)
+        );
+        assert_eq!(record.pending_income, 625);
+    }
+
+    #[test]
+    fn merkle_proof_accepts_every_leaf_and_rejects_tampering() {
+        let investors: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = investors
+            .iter()
+            .enumerate()
+            .map(|(i, investor)| MerkleDistribution::leaf(i as u32, investor, 100 * (i as u64 + 1)))
+            .collect();
+        let left = hash_pair(leaves[0], leaves[1]);
+        let right = hash_pair(leaves[2], leaves[3]);
+        let root = hash_pair(left, right);
+
+        assert!(verify_merkle_proof(&[leaves[1], right], root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[0], right], root, leaves[1]));
+        assert!(verify_merkle_proof(&[leaves[3], left], root, leaves[2]));
+        assert!(verify_merkle_proof(&[leaves[2], left], root, leaves[3]));
+
+        // Wrong amount, wrong index and a tampered sibling all fail
+        let inflated = MerkleDistribution::leaf(0, &investors[0], 101);
+        assert!(!verify_merkle_proof(&[leaves[1], right], root, inflated));
+        let reindexed = MerkleDistribution::leaf(1, &investors[0], 100);
+        assert!(!verify_merkle_proof(&[leaves[1], right], root, reindexed));
+        let mut tampered = right;
+        tampered[0] ^= 1;
+        assert!(!verify_merkle_proof(&[leaves[1], tampered], root, leaves[0]));
+        assert!(!verify_merkle_proof(&[], root, leaves[0]));
+    }
+
+    #[test]
+    fn claimed_bitmap_tracks_each_index_independently() {
+        let num_leaves: u32 = 20;
+        let mut bitmap = ClaimedBitmap {
+            distribution: Pubkey::new_unique(),
+            bits: vec![0; num_leaves.div_ceil(8) as usize],
+        };
+        assert_eq!(bitmap.bits.len(), 3);
+
+        for index in [0, 7, 8, num_leaves - 1] {
+            assert!(!bitmap.is_claimed(index));
+            bitmap.set_claimed(index);
+            assert!(bitmap.is_claimed(index));
+        }
+        for index in 0..num_leaves {
+            assert_eq!(bitmap.is_claimed(index), [0, 7, 8, num_leaves - 1].contains(&index));
+        }
+
+        // Setting a bit twice is idempotent
+        bitmap.set_claimed(7);
+        assert_eq!(bitmap.bits, vec![0b1000_0001, 0b0000_0001, 0b0000_1000]);
+    }
 }
\ No newline at end of file
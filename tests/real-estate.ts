@@ -1,16 +1,163 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { expect } from "chai";
 import { RealEstate } from "../target/types/real_estate";
 
 describe("real-estate", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.realEstate as Program<RealEstate>;
+  const authority = provider.wallet.publicKey;
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+  const [platformState] = PublicKey.findProgramAddressSync(
+    [Buffer.from("platform")],
+    program.programId
+  );
+  const [treasury] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+
+  it("initializes the platform", async () => {
+    await program.methods
+      .initializePlatform(new BN(100), new BN(1000))
+      .accounts({ authority })
+      .rpc();
+
+    const state = await program.account.platformState.fetch(platformState);
+    expect(state.authority.toBase58()).to.equal(authority.toBase58());
+    expect(state.platformFee.toNumber()).to.equal(100);
+    expect(state.governanceThreshold.toNumber()).to.equal(1000);
+    expect(state.totalProperties.toNumber()).to.equal(0);
+    expect(state.totalValueLocked.toNumber()).to.equal(0);
+    expect(state.tradingFee.toNumber()).to.equal(0);
+    expect(state.valueLockedByType.every((v: BN) => v.isZero())).to.be.true;
+  });
+
+  it("initializes the treasury", async () => {
+    await program.methods
+      .initializeTreasury()
+      .accountsPartial({ authority, platformState })
+      .rpc();
+
+    const account = await program.account.platformTreasury.fetch(treasury);
+    expect(account.totalCollected.toNumber()).to.equal(0);
+    expect(account.totalWithdrawn.toNumber()).to.equal(0);
+  });
+
+  it("updates the trading fee", async () => {
+    await program.methods
+      .updateTradingFee(new BN(50))
+      .accountsPartial({ authority, platformState })
+      .rpc();
+
+    const state = await program.account.platformState.fetch(platformState);
+    expect(state.tradingFee.toNumber()).to.equal(50);
+  });
+
+  it("rejects a trading fee above 100%", async () => {
+    try {
+      await program.methods
+        .updateTradingFee(new BN(10001))
+        .accountsPartial({ authority, platformState })
+        .rpc();
+      expect.fail("expected InvalidFee");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "InvalidFee"
+      );
+    }
+  });
+
+  it("rejects trading fee updates from anyone but the authority", async () => {
+    const intruder = Keypair.generate();
+    try {
+      await program.methods
+        .updateTradingFee(new BN(0))
+        .accountsPartial({ authority: intruder.publicKey, platformState })
+        .signers([intruder])
+        .rpc();
+      expect.fail("expected Unauthorized");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "Unauthorized"
+      );
+    }
+  });
+
+  describe("property sales", () => {
+    const property = Keypair.generate();
+    const tokenMint = Keypair.generate();
+    const valuation = new BN(1_000_000);
+
+    before(async () => {
+      await program.methods
+        .initializeProperty(
+          "PROP-SALE-1",
+          new BN(1000),
+          new BN(1_000_000),
+          "1 Main St",
+          { residential: {} },
+          "hash",
+          valuation
+        )
+        .accountsPartial({
+          property: property.publicKey,
+          tokenMint: tokenMint.publicKey,
+          propertyOwner: authority,
+          platformState,
+        })
+        .signers([property, tokenMint])
+        .rpc();
+    });
+
+    it("removes a sold property from TVL once", async () => {
+      const before = await program.account.platformState.fetch(platformState);
+
+      await program.methods
+        .initiatePropertySale(new BN(2_000_000), valuation)
+        .accountsPartial({ property: property.publicKey, authority, platformState })
+        .rpc();
+      await program.methods
+        .executePropertySale(new BN(2_000_000), Keypair.generate().publicKey)
+        .accountsPartial({ property: property.publicKey, authority, platformState })
+        .rpc();
+
+      const after = await program.account.platformState.fetch(platformState);
+      expect(before.totalValueLocked.sub(after.totalValueLocked).eq(valuation)).to.be
+        .true;
+      const sold = await program.account.property.fetch(property.publicKey);
+      expect(sold.isActive).to.be.false;
+    });
+
+    it("rejects selling a property a second time", async () => {
+      const before = await program.account.platformState.fetch(platformState);
+      for (const sale of [
+        program.methods
+          .initiatePropertySale(new BN(2_000_000), valuation)
+          .accountsPartial({ property: property.publicKey, authority, platformState }),
+        program.methods
+          .executePropertySale(new BN(2_000_000), Keypair.generate().publicKey)
+          .accountsPartial({ property: property.publicKey, authority, platformState }),
+      ]) {
+        try {
+          await sale.rpc();
+          expect.fail("expected PropertyNotActive");
+        } catch (err) {
+          expect(err).to.be.instanceOf(anchor.AnchorError);
+          expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+            "PropertyNotActive"
+          );
+        }
+      }
+
+      const after = await program.account.platformState.fetch(platformState);
+      expect(after.totalValueLocked.eq(before.totalValueLocked)).to.be.true;
+    });
   });
 });